@@ -8,7 +8,7 @@ use std::collections::HashMap;
 use std::ffi::{CStr, CString};
 use std::os::raw::{c_char, c_int, c_void, c_float};
 use std::ptr;
-use std::sync::atomic::{AtomicI32, AtomicI64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicI64, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 
 use anyhow::Result;
@@ -31,26 +31,62 @@ use livekit::StreamReader;
 use livekit::webrtc::audio_source::{native::NativeAudioSource, AudioSourceOptions, RtcAudioSource};
 use livekit::webrtc::prelude::AudioFrame;
 use livekit::webrtc::audio_stream::native::NativeAudioStream;
+use livekit::webrtc::video_source::{native::NativeVideoSource, RtcVideoSource, VideoResolution};
+use livekit::webrtc::video_frame::{I420Buffer, VideoFrame, VideoRotation};
+use livekit::webrtc::video_stream::native::NativeVideoStream;
+use livekit::webrtc::yuv_helper;
+use tokio::sync::Notify;
+use std::collections::VecDeque;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use arc_swap::ArcSwapOption;
+use tokio::sync::mpsc;
+use gif::{Encoder, Frame, Repeat};
+use color_quant::NeuQuant;
 
-// --------- Internal logging helpers (gated by LkLogLevel) ---------
-// A message is emitted if msg_level <= current level. Default level is Error (quiet).
+// --------- Internal logging helpers (gated by LkLogLevel + category) ---------
+// A message is recorded/emitted if msg_level <= current level AND its category
+// is set in the category mask. Default level is Error (quiet), all categories on.
 macro_rules! lk_log {
-    ($state:expr, $level:expr, $($arg:tt)*) => {{
-        if ($level as i32) <= ($state.log_level as i32) {
-            println!("[livekit_ffi] {}", format_args!($($arg)*));
+    ($state:expr, $level:expr, $category:expr, $($arg:tt)*) => {{
+        if log_passes_filter(&$state, $level, $category) {
+            record_log_line(&mut $state, $level, $category, format!($($arg)*));
         }
     }};
 }
 macro_rules! lk_log_arc {
-    ($arc:expr, $level:expr, $($arg:tt)*) => {{
-        if let Ok(__g) = $arc.lock() {
-            if ($level as i32) <= (__g.log_level as i32) {
-                println!("[livekit_ffi] {}", format_args!($($arg)*));
+    ($arc:expr, $level:expr, $category:expr, $($arg:tt)*) => {{
+        if let Ok(mut __g) = $arc.lock() {
+            if log_passes_filter(&__g, $level, $category) {
+                record_log_line(&mut __g, $level, $category, format!($($arg)*));
             }
         }
     }};
 }
 
+fn log_passes_filter(g: &ClientState, level: LkLogLevel, category: LkLogCategory) -> bool {
+    (level as i32) <= (g.log_level as i32) && (g.log_category_mask & (1 << category as i32)) != 0
+}
+
+// FIFO-evicting budget for the in-memory log ring; keeps lk_log_drain useful
+// after a crash without letting a chatty session grow it unbounded.
+const LOG_RING_BUDGET_BYTES: usize = 2 * 1024 * 1024;
+
+fn record_log_line(g: &mut ClientState, level: LkLogLevel, category: LkLogCategory, message: String) {
+    println!("[livekit_ffi] {}", message);
+    let timestamp_ns = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as i64)
+        .unwrap_or(0);
+    g.log_records_bytes += message.len() + std::mem::size_of::<LogRecord>();
+    g.log_records.push_back(LogRecord { timestamp_ns, level, category, message });
+    while g.log_records_bytes > LOG_RING_BUDGET_BYTES {
+        match g.log_records.pop_front() {
+            Some(old) => g.log_records_bytes -= old.message.len() + std::mem::size_of::<LogRecord>(),
+            None => break,
+        }
+    }
+}
+
 // --------- C ABI surface ---------
 
 #[repr(C)]
@@ -119,6 +155,27 @@ pub enum LkLogLevel {
     Trace = 4,
 }
 
+/// Tags each recorded log line by subsystem so a host can filter the ring
+/// (and `lk_log_set_filter`'s `category_mask`) by what it's debugging.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LkLogCategory {
+    Connection = 0,
+    Audio = 1,
+    Data = 2,
+    Rtc = 3,
+}
+
+/// One entry pulled from the ring by `lk_log_drain`. `message` is a
+/// `CString::into_raw` pointer the caller must free with `lk_free_str`.
+#[repr(C)]
+pub struct LkLogRecord {
+    pub timestamp_ns: i64,
+    pub level: LkLogLevel,
+    pub category: LkLogCategory,
+    pub message: *const c_char,
+}
+
 #[repr(C)]
 pub struct LkAudioStats {
     pub sample_rate: c_int,
@@ -129,12 +186,104 @@ pub struct LkAudioStats {
     pub overruns: c_int,
 }
 
+/// Layout handed to a host after `lk_audio_track_map_shm` so it can write
+/// samples directly into the track's ring and advance `head_atomic_ptr`
+/// itself, skipping `lk_audio_track_publish_pcm_i16` and the copy/lock it
+/// costs per call. `head_atomic_ptr`/`tail_atomic_ptr` point at `AtomicI64`
+/// sample counts (not byte offsets), taken modulo `capacity_samples`; the
+/// host only ever advances `head`, the 10ms consumer worker only ever
+/// advances `tail`.
+#[repr(C)]
+pub struct LkAudioShmDescriptor {
+    pub base_ptr: *mut i16,
+    pub capacity_samples: usize,
+    pub head_atomic_ptr: *mut i64,
+    pub tail_atomic_ptr: *mut i64,
+}
+
 #[repr(C)]
 pub struct LkDataStats {
     pub reliable_sent_bytes: i64,
     pub reliable_dropped: i64,
     pub lossy_sent_bytes: i64,
     pub lossy_dropped: i64,
+    pub face_frames_sent: i64,
+    pub face_frames_dropped: i64,
+    /// EWMA-smoothed reliable-channel send rate, recomputed every tick of
+    /// `spawn_stats_accumulator`.
+    pub reliable_bps: f64,
+    pub lossy_bps: f64,
+    pub reliable_msgs_per_sec: f64,
+    /// EWMA of send-to-ack latency on the reliable channel, in milliseconds;
+    /// sampled directly in `lk_send_data_ex` since a `ByteStreamWriter::close`
+    /// completing for a reliable send is the closest thing to an ack this
+    /// transport exposes.
+    pub avg_reliable_ack_ms: f64,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LkTrackDirection {
+    Outbound = 0,
+    Inbound = 1,
+}
+
+/// One published or subscribed track's flattened WebRTC stats. Fields that
+/// only apply to one direction (outbound: `target_bitrate_bps`,
+/// `frames_encoded`, `qp`; inbound: `packets_lost`, `jitter_ms`,
+/// `frames_decoded`, `frames_dropped`) are left at 0 for the other.
+#[repr(C)]
+pub struct LkTrackStatsEntry {
+    pub track_sid: *mut c_char,
+    pub direction: LkTrackDirection,
+    /// `0` until the `livekit` crate surfaces the native getStats report;
+    /// `bytes` through `qp` below are meaningless zeros until then, not real
+    /// measurements, so callers must check this before trusting them.
+    pub stats_available: c_int,
+    pub bytes: i64,
+    pub packets: i64,
+    pub packets_lost: i64,
+    pub retransmitted_packets: i64,
+    pub jitter_ms: f64,
+    pub target_bitrate_bps: i64,
+    pub actual_bitrate_bps: i64,
+    pub frames_encoded: i64,
+    pub frames_decoded: i64,
+    pub frames_dropped: i64,
+    pub qp: f64,
+}
+
+#[repr(C)]
+pub struct LkIceStats {
+    /// `0` until the `livekit` crate surfaces the native getStats report;
+    /// the fields below are meaningless zeros until then, not real
+    /// measurements, so callers must check this before trusting them.
+    pub stats_available: c_int,
+    pub current_rtt_ms: f64,
+    pub available_outgoing_bitrate_bps: i64,
+}
+
+#[repr(C)]
+pub struct LkConnectionStats {
+    pub tracks: *mut LkTrackStatsEntry,
+    pub track_count: usize,
+    pub ice: LkIceStats,
+}
+
+/// Follows the cubeb sample-format model so callers can distinguish a
+/// platform's native byte order instead of assuming little-endian. Only the
+/// `*LE` variants are currently accepted by `lk_set_audio_output_format` -
+/// every byte of PCM this crate touches (cpal capture, the i16/f32 publish
+/// paths, the audio ring) is already native-endian, and every target
+/// Unreal ships on (x86/ARM) is little-endian - but hosts on a big-endian
+/// platform can still name their format correctly instead of lying about it.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LkSampleFormat {
+    S16LE = 0,
+    S16BE = 1,
+    Float32LE = 2,
+    Float32BE = 3,
 }
 
 #[repr(C)]
@@ -143,6 +292,10 @@ pub struct LkAudioTrackConfig {
     pub sample_rate: c_int,
     pub channels: c_int,
     pub buffer_ms: c_int,
+    /// Format the host intends to push in (picks between
+    /// `lk_audio_track_publish_pcm_i16`/`_f32`); doesn't change how the
+    /// track itself is created.
+    pub input_format: LkSampleFormat,
 }
 
 struct AudioTrackHandleRef {
@@ -153,6 +306,31 @@ struct AudioTrackHandleRef {
 #[repr(C)]
 pub struct LkAudioTrackHandle(AudioTrackHandleRef);
 
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LkVideoFormat {
+    I420 = 0,
+    Nv12 = 1,
+    Rgba = 2,
+    Bgra = 3,
+}
+
+#[repr(C)]
+pub struct LkVideoTrackConfig {
+    pub track_name: *const c_char,
+    pub width: c_int,
+    pub height: c_int,
+    pub max_queued_frames: c_int,
+}
+
+struct VideoTrackHandleRef {
+    client: Arc<Mutex<ClientState>>,
+    track_id: u64,
+}
+
+#[repr(C)]
+pub struct LkVideoTrackHandle(VideoTrackHandleRef);
+
 #[repr(C)]
 pub struct LkClientHandle {
     _private: [u8; 0],
@@ -179,6 +357,173 @@ impl AudioRing {
     }
 }
 
+/// Lock-free SPSC ring a host writes samples into directly after
+/// `lk_audio_track_map_shm`, bypassing `AudioPipeline::push` (and the
+/// ClientState lock / Vec copy it costs per call) entirely. `head`/`tail`
+/// are monotonically increasing sample counts, indexed into `buf` modulo
+/// `capacity`; the producer (host) only advances `head`, the consumer (the
+/// 10ms worker below) only advances `tail`, so each side only ever reads
+/// the other's atomic.
+struct ShmAudioRing {
+    buf: Vec<i16>,
+    capacity: usize,
+    head: AtomicI64,
+    tail: AtomicI64,
+}
+
+impl ShmAudioRing {
+    fn new(capacity_samples: usize) -> Self {
+        Self {
+            buf: vec![0i16; capacity_samples.max(1)],
+            capacity: capacity_samples.max(1),
+            head: AtomicI64::new(0),
+            tail: AtomicI64::new(0),
+        }
+    }
+
+    fn base_ptr(&self) -> *mut i16 {
+        self.buf.as_ptr() as *mut i16
+    }
+
+    /// Fills `out` with the next `out.len()` samples, zero-filling (and
+    /// returning `false` for) whatever hasn't been written yet.
+    fn pop_into(&self, out: &mut [i16]) -> bool {
+        let head = self.head.load(Ordering::Acquire);
+        let tail = self.tail.load(Ordering::Relaxed);
+        let available = (head - tail).max(0) as usize;
+        let to_copy = available.min(out.len());
+        let base = self.base_ptr();
+        for (i, slot) in out.iter_mut().enumerate().take(to_copy) {
+            let idx = (tail as usize + i) % self.capacity;
+            // The host writes into `base` concurrently from outside Rust's
+            // aliasing model; read through the raw pointer rather than a
+            // safe slice index into `self.buf`.
+            *slot = unsafe { std::ptr::read_volatile(base.add(idx)) };
+        }
+        for slot in &mut out[to_copy..] {
+            *slot = 0;
+        }
+        self.tail.store(tail + to_copy as i64, Ordering::Release);
+        to_copy == out.len()
+    }
+}
+
+#[cfg(test)]
+mod shm_audio_ring_tests {
+    use super::ShmAudioRing;
+    use std::sync::atomic::Ordering;
+
+    #[test]
+    fn pop_into_empty_ring_zero_fills_and_reports_underrun() {
+        let ring = ShmAudioRing::new(8);
+        let mut out = vec![42i16; 4];
+        let complete = ring.pop_into(&mut out);
+        assert!(!complete);
+        assert_eq!(out, vec![0; 4]);
+    }
+
+    #[test]
+    fn pop_into_returns_written_samples_in_order() {
+        let ring = ShmAudioRing::new(8);
+        for (i, v) in [1i16, 2, 3, 4].iter().enumerate() {
+            unsafe { std::ptr::write_volatile(ring.base_ptr().add(i), *v) };
+        }
+        ring.head.store(4, Ordering::Release);
+        let mut out = vec![0i16; 4];
+        let complete = ring.pop_into(&mut out);
+        assert!(complete);
+        assert_eq!(out, vec![1, 2, 3, 4]);
+        assert_eq!(ring.tail.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn pop_into_partial_backlog_zero_fills_the_remainder() {
+        let ring = ShmAudioRing::new(8);
+        unsafe { std::ptr::write_volatile(ring.base_ptr(), 7) };
+        ring.head.store(1, Ordering::Release);
+        let mut out = vec![9i16; 3];
+        let complete = ring.pop_into(&mut out);
+        assert!(!complete);
+        assert_eq!(out, vec![7, 0, 0]);
+    }
+
+    #[test]
+    fn pop_into_indexes_wrap_around_capacity() {
+        let ring = ShmAudioRing::new(4);
+        ring.tail.store(4, Ordering::Relaxed);
+        ring.head.store(4, Ordering::Relaxed);
+        unsafe { std::ptr::write_volatile(ring.base_ptr(), 11) };
+        ring.head.store(5, Ordering::Release);
+        let mut out = vec![0i16; 1];
+        assert!(ring.pop_into(&mut out));
+        assert_eq!(out, vec![11]);
+    }
+}
+
+/// Phase-continuous linear-interpolation resampler from an input rate to a
+/// fixed output rate, kept per-pipeline so a track accepts pushes at whatever
+/// rate the caller has on hand (notably UE's native 48kHz f32) instead of
+/// hard-failing on a mismatch. `pos`/the carried trailing frame are state that
+/// must survive across calls so block boundaries don't click.
+struct Resampler {
+    channels: usize,
+    step: f64,
+    pos: f64,
+    last_frame: Vec<i16>,
+}
+
+impl Resampler {
+    fn new(channels: usize, in_rate: u32, out_rate: u32) -> Self {
+        Self {
+            channels,
+            step: in_rate as f64 / out_rate.max(1) as f64,
+            pos: 0.0,
+            last_frame: vec![0i16; channels.max(1)],
+        }
+    }
+
+    fn set_rate(&mut self, in_rate: u32, out_rate: u32) {
+        self.step = in_rate as f64 / out_rate.max(1) as f64;
+    }
+
+    /// Appends resampled interleaved frames for `input` (interleaved, a
+    /// multiple of `self.channels` samples long) onto `out`.
+    fn process(&mut self, input: &[i16], out: &mut Vec<i16>) {
+        let channels = self.channels;
+        if channels == 0 {
+            return;
+        }
+        let in_frames = input.len() / channels;
+        if in_frames == 0 {
+            return;
+        }
+        // Virtual frame 0 is the trailing frame carried from the previous
+        // call; frames 1..=in_frames are this call's input.
+        let frame_at = |idx: usize, ch: usize, last_frame: &[i16]| -> i16 {
+            if idx == 0 {
+                last_frame[ch]
+            } else {
+                input[(idx - 1) * channels + ch]
+            }
+        };
+        while (self.pos.floor() as usize) < in_frames {
+            let idx0 = self.pos.floor() as usize;
+            let frac = self.pos - idx0 as f64;
+            for ch in 0..channels {
+                let s0 = frame_at(idx0, ch, &self.last_frame) as f64;
+                let s1 = frame_at(idx0 + 1, ch, &self.last_frame) as f64;
+                let interp = s0 * (1.0 - frac) + s1 * frac;
+                out.push(interp.round().clamp(i16::MIN as f64, i16::MAX as f64) as i16);
+            }
+            self.pos += self.step;
+        }
+        for ch in 0..channels {
+            self.last_frame[ch] = input[(in_frames - 1) * channels + ch];
+        }
+        self.pos -= in_frames as f64;
+    }
+}
+
 #[allow(dead_code)]
 struct AudioPipeline {
     label: String,
@@ -188,6 +533,15 @@ struct AudioPipeline {
     local_track: LocalAudioTrack,
     src: NativeAudioSource,
     worker: JoinHandle<()>,
+    // Set by lk_audio_track_map_shm; when present the consumer worker reads
+    // from it instead of `ring`, and lk_audio_track_publish_pcm_i16 pushes
+    // are ignored until lk_audio_track_unmap_shm clears it.
+    shm: Arc<ArcSwapOption<ShmAudioRing>>,
+    // Converts whatever rate a caller pushes at to `sample_rate`. When the
+    // rates already match, samples pass through unchanged apart from a
+    // one-frame delay (the carried trailing frame is emitted first), since a
+    // step of 1.0 always lands exactly on input samples.
+    resampler: Resampler,
 }
 
 impl Drop for AudioPipeline {
@@ -197,7 +551,14 @@ impl Drop for AudioPipeline {
 }
 
 impl AudioPipeline {
-    fn push(&mut self, data: &[i16]) -> Result<()> {
+    /// Pushes `data` (interleaved PCM i16 at `in_rate`), resampling to the
+    /// pipeline's own rate first if the two differ.
+    fn push(&mut self, data: &[i16], in_rate: u32) -> Result<()> {
+        if self.shm.load().is_some() {
+            // A host has taken over via lk_audio_track_map_shm; pushes
+            // through the old copying path would just race the shm ring.
+            return Ok(());
+        }
         if data.len() % self.channels as usize != 0 {
             anyhow::bail!(
                 "pcm payload len {} is not divisible by channel count {}",
@@ -205,10 +566,14 @@ impl AudioPipeline {
                 self.channels
             );
         }
+        self.resampler.set_rate(in_rate, self.sample_rate);
+        let mut resampled = Vec::with_capacity(data.len());
+        self.resampler.process(data, &mut resampled);
+
         let mut pushed = 0usize;
         let mut dropped = false;
-        while pushed < data.len() {
-            match self.ring.prod.push(data[pushed]) {
+        while pushed < resampled.len() {
+            match self.ring.prod.push(resampled[pushed]) {
                 Ok(_) => pushed += 1,
                 Err(_) => {
                     dropped = true;
@@ -223,6 +588,66 @@ impl AudioPipeline {
     }
 }
 
+struct PendingVideoFrame {
+    buf: Vec<u8>,
+    width: u32,
+    height: u32,
+    format: LkVideoFormat,
+    timestamp_us: i64,
+}
+
+// Bounded, newest-frame-wins queue: pushing past capacity drops the oldest
+// queued frame rather than blocking the producer or growing unbounded.
+struct VideoFrameQueue {
+    frames: VecDeque<PendingVideoFrame>,
+    capacity: usize,
+}
+
+impl VideoFrameQueue {
+    fn push(&mut self, frame: PendingVideoFrame) -> bool {
+        let dropped = if self.frames.len() >= self.capacity {
+            self.frames.pop_front();
+            true
+        } else {
+            false
+        };
+        self.frames.push_back(frame);
+        dropped
+    }
+}
+
+#[allow(dead_code)]
+struct VideoPipeline {
+    label: String,
+    width: u32,
+    height: u32,
+    queue: Arc<Mutex<VideoFrameQueue>>,
+    notify: Arc<Notify>,
+    local_track: LocalVideoTrack,
+    src: NativeVideoSource,
+    worker: JoinHandle<()>,
+    dropped_frames: Arc<AtomicI32>,
+}
+
+impl Drop for VideoPipeline {
+    fn drop(&mut self) {
+        self.worker.abort();
+    }
+}
+
+impl VideoPipeline {
+    fn push(&mut self, frame: PendingVideoFrame) {
+        let dropped = {
+            let mut q = self.queue.lock().unwrap();
+            q.push(frame)
+        };
+        if dropped {
+            self.dropped_frames.fetch_add(1, Ordering::Relaxed);
+        }
+        self.notify.notify_one();
+    }
+}
+
 struct UserPtr(*mut c_void);
 unsafe impl Send for UserPtr {}
 unsafe impl Sync for UserPtr {}
@@ -248,6 +673,7 @@ impl Default for AudioPublishOptions {
 struct AudioOutputFormat {
     sample_rate: i32,
     channels: i32,
+    format: LkSampleFormat,
 }
 
 impl Default for AudioOutputFormat {
@@ -255,6 +681,7 @@ impl Default for AudioOutputFormat {
         Self {
             sample_rate: 48_000,
             channels: 1,
+            format: LkSampleFormat::S16LE,
         }
     }
 }
@@ -279,6 +706,24 @@ struct DataStatsCounters {
     reliable_dropped: AtomicI64,
     lossy_sent_bytes: AtomicI64,
     lossy_dropped: AtomicI64,
+    face_frames_sent: AtomicI64,
+    face_frames_dropped: AtomicI64,
+
+    // Cumulative reliable message count; the byte counters above don't track
+    // message count, but `reliable_msgs_per_sec` needs one.
+    reliable_msgs_sent: AtomicI64,
+    // Last-tick snapshot spawn_stats_accumulator diffs against to get a
+    // per-tick delta without needing a separate ring of timestamped samples.
+    last_reliable_bytes: AtomicI64,
+    last_lossy_bytes: AtomicI64,
+    last_reliable_msgs: AtomicI64,
+
+    // EWMA-smoothed rates/latency, stored as raw f64 bits (no AtomicF64 in
+    // std). Read/written with ewma_update()/load_f64() below.
+    reliable_bps: AtomicU64,
+    lossy_bps: AtomicU64,
+    reliable_msgs_per_sec: AtomicU64,
+    avg_reliable_ack_ms: AtomicU64,
 }
 
 impl Default for DataStatsCounters {
@@ -288,467 +733,1989 @@ impl Default for DataStatsCounters {
             reliable_dropped: AtomicI64::new(0),
             lossy_sent_bytes: AtomicI64::new(0),
             lossy_dropped: AtomicI64::new(0),
+            face_frames_sent: AtomicI64::new(0),
+            face_frames_dropped: AtomicI64::new(0),
+            reliable_msgs_sent: AtomicI64::new(0),
+            last_reliable_bytes: AtomicI64::new(0),
+            last_lossy_bytes: AtomicI64::new(0),
+            last_reliable_msgs: AtomicI64::new(0),
+            reliable_bps: AtomicU64::new(0),
+            lossy_bps: AtomicU64::new(0),
+            reliable_msgs_per_sec: AtomicU64::new(0),
+            avg_reliable_ack_ms: AtomicU64::new(0),
         }
     }
 }
 
-struct ClientState {
-    room: Option<Room>,
-    audio_tracks: HashMap<u64, AudioPipeline>,
-    default_audio_track_id: Option<u64>,
-    next_audio_track_id: u64,
-    rt: Arc<Runtime>,
-    
-    // Callbacks
-    data_cb: Option<(extern "C" fn(*mut c_void, *const u8, usize), UserPtr)>,
-    data_cb_ex: Option<(extern "C" fn(*mut c_void, *const c_char, LkReliability, *const u8, usize), UserPtr)>,
-    audio_cb: Option<(extern "C" fn(*mut c_void, *const i16, usize, c_int, c_int), UserPtr)>,
-    audio_format_change_cb: Option<(extern "C" fn(*mut c_void, c_int, c_int), UserPtr)>,
-    connection_cb: Option<(extern "C" fn(*mut c_void, LkConnectionState, c_int, *const c_char), UserPtr)>,
-    
-    // Configuration
-    role: LkRole,
-    audio_publish_opts: AudioPublishOptions,
-    audio_output_format: AudioOutputFormat,
-    data_labels: DataLabels,
-    log_level: LkLogLevel,
-    
-    // Statistics
-    data_stats: Arc<DataStatsCounters>,
+fn load_f64(cell: &AtomicU64) -> f64 {
+    f64::from_bits(cell.load(Ordering::Relaxed))
 }
 
-struct Client(Arc<Mutex<ClientState>>);
+/// Blends `sample` into `cell` with smoothing factor `alpha` (0 snaps to the
+/// sample on the first update since the stored value starts at zero bits,
+/// which is also a valid "no traffic yet" reading).
+fn ewma_update(cell: &AtomicU64, sample: f64, alpha: f64) {
+    let prev = load_f64(cell);
+    let next = if prev == 0.0 { sample } else { prev + alpha * (sample - prev) };
+    cell.store(next.to_bits(), Ordering::Relaxed);
+}
 
-static RT: OnceCell<Arc<Runtime>> = OnceCell::new();
-fn runtime() -> Arc<Runtime> {
-    RT.get_or_init(|| Arc::new(Runtime::new().expect("tokio runtime"))).clone()
+/// Sorted, coalesced set of half-open byte ranges `[start, end)` that have
+/// arrived for an in-progress inbound byte stream. Lets stream completion
+/// tell a truly contiguous transfer apart from one with a gap, without
+/// assuming chunks arrive strictly in order.
+#[derive(Default)]
+struct RangeSet {
+    ranges: Vec<(u64, u64)>,
 }
 
-unsafe fn cstr<'a>(p: *const c_char) -> Result<&'a str> {
-    if p.is_null() {
-        anyhow::bail!("null pointer")
+impl RangeSet {
+    fn insert(&mut self, start: u64, end: u64) {
+        if start >= end {
+            return;
+        }
+        let idx = self.ranges.partition_point(|&(s, _)| s <= start);
+        self.ranges.insert(idx, (start, end));
+        let mut i = idx.saturating_sub(1);
+        while i + 1 < self.ranges.len() {
+            let (s1, e1) = self.ranges[i];
+            let (s2, e2) = self.ranges[i + 1];
+            if e1 >= s2 {
+                self.ranges[i] = (s1, e1.max(e2));
+                self.ranges.remove(i + 1);
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    /// `true` if this set is exactly one range covering `[0, total)`. A
+    /// zero-length transfer has nothing to receive, so an empty set is
+    /// trivially contiguous in that case rather than a reported gap.
+    fn is_contiguous_from_zero(&self, total: u64) -> bool {
+        if total == 0 {
+            return self.ranges.is_empty();
+        }
+        matches!(self.ranges.as_slice(), [(0, end)] if *end == total)
     }
-    Ok(CStr::from_ptr(p).to_str()?)
 }
 
-// --------- FFI functions ---------
+#[cfg(test)]
+mod range_set_tests {
+    use super::RangeSet;
 
-#[no_mangle]
-pub extern "C" fn lk_client_create() -> *mut LkClientHandle {
-    let state = ClientState {
-        room: None,
-        audio_tracks: HashMap::new(),
-        default_audio_track_id: None,
-        next_audio_track_id: 1,
-        rt: runtime(),
-        data_cb: None,
-        data_cb_ex: None,
-        audio_cb: None,
-        audio_format_change_cb: None,
-        connection_cb: None,
-        role: LkRole::Both,
-        audio_publish_opts: AudioPublishOptions::default(),
-        audio_output_format: AudioOutputFormat::default(),
-        data_labels: DataLabels::default(),
-        log_level: LkLogLevel::Error,
-        data_stats: Arc::new(DataStatsCounters::default()),
-    };
-    let boxed = Box::new(Client(Arc::new(Mutex::new(state))));
-    Box::into_raw(boxed) as *mut LkClientHandle
-}
+    #[test]
+    fn empty_zero_length_transfer_is_contiguous() {
+        let set = RangeSet::default();
+        assert!(set.is_contiguous_from_zero(0));
+    }
 
-#[no_mangle]
-pub extern "C" fn lk_client_destroy(client: *mut LkClientHandle) {
-    if client.is_null() {
-        return;
+    #[test]
+    fn nonempty_set_is_not_contiguous_for_zero_total() {
+        let mut set = RangeSet::default();
+        set.insert(0, 5);
+        assert!(!set.is_contiguous_from_zero(0));
+    }
+
+    #[test]
+    fn single_range_covering_total_is_contiguous() {
+        let mut set = RangeSet::default();
+        set.insert(0, 10);
+        assert!(set.is_contiguous_from_zero(10));
+    }
+
+    #[test]
+    fn gap_is_not_contiguous() {
+        let mut set = RangeSet::default();
+        set.insert(0, 4);
+        set.insert(6, 10);
+        assert!(!set.is_contiguous_from_zero(10));
+    }
+
+    #[test]
+    fn out_of_order_adjacent_inserts_coalesce() {
+        let mut set = RangeSet::default();
+        set.insert(6, 10);
+        set.insert(0, 4);
+        set.insert(4, 6);
+        assert!(set.is_contiguous_from_zero(10));
+    }
+
+    #[test]
+    fn empty_insert_is_ignored() {
+        let mut set = RangeSet::default();
+        set.insert(5, 5);
+        assert!(set.ranges.is_empty());
     }
-    unsafe { drop(Box::from_raw(client as *mut Client)); }
 }
 
-#[no_mangle]
-pub extern "C" fn lk_client_set_data_callback(
-    client: *mut LkClientHandle,
-    cb: Option<extern "C" fn(user: *mut c_void, bytes: *const u8, len: usize)>,
-    user: *mut c_void,
-) -> LkResult {
-    if client.is_null() { return err(1, "client null"); }
-    let c = unsafe { &*(client as *const Client) };
-    let mut g = c.0.lock().unwrap();
-    g.data_cb = cb.map(|f| (f, UserPtr(user)));
-    ok()
+/// Internal record kept in `ClientState.log_records`; distinct from the
+/// FFI-facing `LkLogRecord`, whose `message` is a caller-owned C string.
+struct LogRecord {
+    timestamp_ns: i64,
+    level: LkLogLevel,
+    category: LkLogCategory,
+    message: String,
 }
 
-#[no_mangle]
-pub extern "C" fn lk_client_set_audio_callback(
-    client: *mut LkClientHandle,
-    cb: Option<extern "C" fn(user: *mut c_void, pcm: *const i16, frames_per_channel: usize, channels: c_int, sample_rate: c_int)>,
-    user: *mut c_void,
-) -> LkResult {
-    if client.is_null() { return err(1, "client null"); }
-    let c = unsafe { &*(client as *const Client) };
-    let mut g = c.0.lock().unwrap();
-    g.audio_cb = cb.map(|f| (f, UserPtr(user)));
-    ok()
+struct StreamCallbacks {
+    open: extern "C" fn(user: *mut c_void, stream_id: u64, topic: *const c_char, identity: *const c_char, total_length: i64),
+    chunk: extern "C" fn(user: *mut c_void, stream_id: u64, offset: u64, ptr: *const u8, len: usize),
+    close: extern "C" fn(user: *mut c_void, stream_id: u64, error_code: c_int, message: *const c_char),
+    user: UserPtr,
 }
 
-#[no_mangle]
-pub extern "C" fn lk_client_set_data_callback_ex(
-    client: *mut LkClientHandle,
-    cb: Option<extern "C" fn(user: *mut c_void, label: *const c_char, reliability: LkReliability, bytes: *const u8, len: usize)>,
-    user: *mut c_void,
-) -> LkResult {
-    if client.is_null() { return err(1, "client null"); }
-    let c = unsafe { &*(client as *const Client) };
-    let mut g = c.0.lock().unwrap();
-    g.data_cb_ex = cb.map(|f| (f, UserPtr(user)));
-    ok()
+/// Bookkeeping for one inbound byte stream between `ByteStreamOpened` and its
+/// `close_cb`. The receive loop itself runs as its own task (see
+/// `lk_set_stream_cb`'s connect-path handlers) so a slow or aborted transfer
+/// can't stall the rest of the room event loop.
+struct StreamState {
+    handle: JoinHandle<()>,
+    received: RangeSet,
+    total_length: Option<u64>,
 }
 
-#[no_mangle]
-pub extern "C" fn lk_set_audio_format_change_callback(
-    client: *mut LkClientHandle,
-    cb: Option<extern "C" fn(user: *mut c_void, sample_rate: c_int, channels: c_int)>,
-    user: *mut c_void,
-) -> LkResult {
-    if client.is_null() { return err(1, "client null"); }
-    let c = unsafe { &*(client as *const Client) };
-    let mut g = c.0.lock().unwrap();
-    g.audio_format_change_cb = cb.map(|f| (f, UserPtr(user)));
-    ok()
+// --------- RPC over data channels ---------
+//
+// Request/response calls share the generic byte-stream transport (same one
+// `lk_send_data_ex`/`ByteStreamOpened` use) on a dedicated topic, with a small
+// header prepended to the caller's payload so responses can be matched back
+// to the call that sent them without a second channel.
+
+/// Reliable byte-stream topic reserved for RPC frames; `ByteStreamOpened`
+/// events on this topic are parsed as `RpcFrame`s rather than handed to
+/// `data_cb`.
+const RPC_TOPIC: &str = "lk-rpc";
+/// Same cap `lk_send_data_ex` enforces for reliable sends, since RPC frames
+/// ride the same reliable transport.
+const RPC_MAX_PAYLOAD: usize = 15 * 1024;
+
+const RPC_KIND_REQUEST: u8 = 1;
+const RPC_KIND_RESPONSE: u8 = 2;
+/// Fire-and-forget notification: no `rpc_pending` entry is registered and no
+/// Response frame is ever sent back, unlike a Request/Response pair.
+const RPC_KIND_EVENT: u8 = 3;
+
+const RPC_STATUS_OK: c_int = 0;
+const RPC_STATUS_TIMEOUT: c_int = 1;
+const RPC_STATUS_NO_HANDLER: c_int = 2;
+const RPC_STATUS_HANDLER_ERROR: c_int = 3;
+
+/// Wire layout: `u64 request_id | u8 kind | u8 status | u32 method_len | method bytes | payload`.
+/// `status` is only meaningful on a Response frame; `method` is empty on one.
+struct RpcFrame<'a> {
+    request_id: u64,
+    kind: u8,
+    status: u8,
+    method: &'a str,
+    payload: &'a [u8],
 }
 
-#[no_mangle]
-pub extern "C" fn lk_set_connection_callback(
-    client: *mut LkClientHandle,
-    cb: Option<extern "C" fn(user: *mut c_void, state: LkConnectionState, reason_code: c_int, message: *const c_char)>,
-    user: *mut c_void,
-) -> LkResult {
-    if client.is_null() { return err(1, "client null"); }
-    let c = unsafe { &*(client as *const Client) };
-    let mut g = c.0.lock().unwrap();
-    g.connection_cb = cb.map(|f| (f, UserPtr(user)));
-    ok()
+fn encode_rpc_request(request_id: u64, method: &str, payload: &[u8]) -> Vec<u8> {
+    let method_bytes = method.as_bytes();
+    let mut buf = Vec::with_capacity(14 + method_bytes.len() + payload.len());
+    buf.extend_from_slice(&request_id.to_le_bytes());
+    buf.push(RPC_KIND_REQUEST);
+    buf.push(0);
+    buf.extend_from_slice(&(method_bytes.len() as u32).to_le_bytes());
+    buf.extend_from_slice(method_bytes);
+    buf.extend_from_slice(payload);
+    buf
 }
 
-// --------- Configuration Functions ---------
+fn encode_rpc_response(request_id: u64, status: u8, payload: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(14 + payload.len());
+    buf.extend_from_slice(&request_id.to_le_bytes());
+    buf.push(RPC_KIND_RESPONSE);
+    buf.push(status);
+    buf.extend_from_slice(&0u32.to_le_bytes());
+    buf.extend_from_slice(payload);
+    buf
+}
 
-#[no_mangle]
-pub extern "C" fn lk_set_audio_publish_options(
-    client: *mut LkClientHandle,
-    bitrate_bps: c_int,
-    enable_dtx: c_int,
-    stereo: c_int,
-) -> LkResult {
-    if client.is_null() { return err(1, "client null"); }
-    let c = unsafe { &*(client as *const Client) };
-    let mut g = c.0.lock().unwrap();
-    g.audio_publish_opts = AudioPublishOptions {
-        bitrate_bps,
-        enable_dtx: enable_dtx != 0,
-        stereo: stereo != 0,
-    };
-    lk_log!(g, LkLogLevel::Debug, "Audio publish options set: bitrate={}bps, dtx={}, stereo={}", bitrate_bps, enable_dtx != 0, stereo != 0);
-    ok()
+/// An Event frame carries no correlation id worth tracking, since nothing
+/// waits on it; `request_id` is always 0 on the wire.
+fn encode_rpc_event(method: &str, payload: &[u8]) -> Vec<u8> {
+    let method_bytes = method.as_bytes();
+    let mut buf = Vec::with_capacity(14 + method_bytes.len() + payload.len());
+    buf.extend_from_slice(&0u64.to_le_bytes());
+    buf.push(RPC_KIND_EVENT);
+    buf.push(0);
+    buf.extend_from_slice(&(method_bytes.len() as u32).to_le_bytes());
+    buf.extend_from_slice(method_bytes);
+    buf.extend_from_slice(payload);
+    buf
 }
 
-#[no_mangle]
-pub extern "C" fn lk_set_audio_output_format(
-    client: *mut LkClientHandle,
-    sample_rate: c_int,
-    channels: c_int,
-) -> LkResult {
-    if client.is_null() { return err(1, "client null"); }
-    if sample_rate <= 0 || channels <= 0 {
-        return err(5, "invalid audio output format");
+fn decode_rpc_frame(buf: &[u8]) -> Option<RpcFrame<'_>> {
+    if buf.len() < 14 {
+        return None;
     }
-    let c = unsafe { &*(client as *const Client) };
-    let mut g = c.0.lock().unwrap();
-    g.audio_output_format = AudioOutputFormat {
-        sample_rate,
-        channels,
-    };
-    lk_log!(g, LkLogLevel::Debug, "Audio output format set: sr={}Hz, ch={}", sample_rate, channels);
-    ok()
+    let request_id = u64::from_le_bytes(buf[0..8].try_into().ok()?);
+    let kind = buf[8];
+    let status = buf[9];
+    let method_len = u32::from_le_bytes(buf[10..14].try_into().ok()?) as usize;
+    if buf.len() < 14 + method_len {
+        return None;
+    }
+    let method = std::str::from_utf8(&buf[14..14 + method_len]).ok()?;
+    let payload = &buf[14 + method_len..];
+    Some(RpcFrame { request_id, kind, status, method, payload })
 }
 
-#[no_mangle]
-pub extern "C" fn lk_set_default_data_labels(
-    client: *mut LkClientHandle,
-    reliable_label: *const c_char,
-    lossy_label: *const c_char,
-) -> LkResult {
-    if client.is_null() { return err(1, "client null"); }
-    let c = unsafe { &*(client as *const Client) };
-    let mut g = c.0.lock().unwrap();
-    
-    if !reliable_label.is_null() {
-        if let Ok(s) = unsafe { cstr(reliable_label) } {
-            g.data_labels.reliable = s.to_string();
-        }
-    }
-    if !lossy_label.is_null() {
-        if let Ok(s) = unsafe { cstr(lossy_label) } {
-            g.data_labels.lossy = s.to_string();
-        }
-    }
-    
-    lk_log!(g, LkLogLevel::Debug, "Data labels set: reliable='{}', lossy='{}'", g.data_labels.reliable, g.data_labels.lossy);
-    ok()
+type RpcReplyFn = extern "C" fn(
+    user: *mut c_void,
+    request_id: u64,
+    status: c_int,
+    payload: *const u8,
+    payload_len: usize,
+);
+
+/// Handler for an incoming RPC request; writes its response into the
+/// Rust-owned `out_buf` (capacity `out_buf_cap`, capped at `RPC_MAX_PAYLOAD`)
+/// and sets `*out_len`. Return 0 on success; any other value sends back a
+/// Response with status `RPC_STATUS_HANDLER_ERROR` and an empty payload.
+type RpcHandlerFn = extern "C" fn(
+    user: *mut c_void,
+    method: *const c_char,
+    payload: *const u8,
+    payload_len: usize,
+    out_buf: *mut u8,
+    out_buf_cap: usize,
+    out_len: *mut usize,
+) -> c_int;
+
+struct RpcPendingCall {
+    reply_cb: RpcReplyFn,
+    user: UserPtr,
+    deadline: std::time::Instant,
 }
 
-#[no_mangle]
-pub extern "C" fn lk_set_reconnect_backoff(
-    client: *mut LkClientHandle,
-    _initial_ms: c_int,
-    _max_ms: c_int,
-    _multiplier: c_float,
-) -> LkResult {
-    // Note: LiveKit SDK manages reconnection internally; this is a placeholder
-    // for future implementation if SDK exposes these controls
-    if !client.is_null() {
-        let c = unsafe { &*(client as *const Client) };
-        if let Ok(g) = c.0.lock() {
-            lk_log!(g, LkLogLevel::Trace, "Reconnect backoff configuration requested (not yet implemented)");
-        }
-    }
-    ok()
+struct RpcHandlerEntry {
+    handler: RpcHandlerFn,
+    user: UserPtr,
 }
 
-#[no_mangle]
-pub extern "C" fn lk_refresh_token(
-    _client: *mut LkClientHandle,
-    _token: *const c_char,
-) -> LkResult {
-    // Note: Token refresh at runtime is not currently supported by LiveKit SDK
-    // Best practice is to disconnect and reconnect with new token
-    err(501, "Token refresh not supported; use disconnect + reconnect")
+type RpcEventFn = extern "C" fn(
+    user: *mut c_void,
+    method: *const c_char,
+    payload: *const u8,
+    payload_len: usize,
+);
+
+// --------- Live Link Face transport ---------
+//
+// ARKit blendshape frames ride the same byte-stream transport as RPC frames,
+// on their own reserved topic, encoded/decoded straight to/from the wire
+// format Live Link Face sends over UDP so a UE client can drive a MetaHuman
+// from either transport with the same parser.
+
+/// Reliable byte-stream topic reserved for Live Link Face frames.
+const FACE_TOPIC: &str = "lk-face";
+/// Live Link Face packet version this backend speaks; frames with any other
+/// version are dropped rather than mis-parsed.
+const FACE_PROTOCOL_VERSION: i32 = 6;
+
+/// One decoded (or about-to-be-encoded) Live Link Face frame: 61 blendshape
+/// curves (52 ARKit + head yaw/pitch/roll + per-eye yaw/pitch/roll) plus the
+/// subject/frame-time metadata the protocol carries alongside them.
+#[repr(C)]
+pub struct LkFaceFrame {
+    pub device_id: *const c_char,
+    pub subject_name: *const c_char,
+    pub frame_number: c_int,
+    pub subframe: c_int,
+    pub frame_rate_num: c_int,
+    pub frame_rate_den: c_int,
+    pub blendshapes: *const c_float,
+    pub blendshape_count: usize,
 }
 
-#[no_mangle]
-pub extern "C" fn lk_set_role(
-    _client: *mut LkClientHandle,
-    _role: LkRole,
-    _auto_subscribe: c_int,
-) -> LkResult {
-    // Note: Dynamic role switching without reconnect is not currently supported
-    // Best practice is to disconnect and reconnect with new role
-    err(501, "Dynamic role switching not supported; use disconnect + reconnect with new role")
+/// Owning counterpart to `LkFaceFrame` for a frame just decoded off the wire;
+/// keeps the `CString`s/`Vec` alive for the duration of the face callback.
+struct DecodedFaceFrame {
+    device_id: CString,
+    subject_name: CString,
+    frame_number: i32,
+    subframe: i32,
+    frame_rate_num: i32,
+    frame_rate_den: i32,
+    blendshapes: Vec<f32>,
 }
 
-#[no_mangle]
-pub extern "C" fn lk_set_log_level(
-    client: *mut LkClientHandle,
-    level: LkLogLevel,
-) -> LkResult {
-    if client.is_null() { return err(1, "client null"); }
-    let c = unsafe { &*(client as *const Client) };
-    let mut g = c.0.lock().unwrap();
-    g.log_level = level;
-    lk_log!(g, LkLogLevel::Debug, "Log level set to: {:?}", level);
-    ok()
+fn write_be_string(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(&(s.len() as i32).to_be_bytes());
+    buf.extend_from_slice(s.as_bytes());
 }
 
-// --------- Connection Functions ---------
+fn read_be_i32(buf: &[u8], pos: &mut usize) -> Option<i32> {
+    let v = i32::from_be_bytes(buf.get(*pos..*pos + 4)?.try_into().ok()?);
+    *pos += 4;
+    Some(v)
+}
 
-#[no_mangle]
-pub extern "C" fn lk_connect(
-    client: *mut LkClientHandle,
-    url: *const c_char,
-    token: *const c_char,
-) -> LkResult {
-    // Default to Both
-    lk_connect_with_role(client, url, token, LkRole::Both)
+fn read_be_string(buf: &[u8], pos: &mut usize) -> Option<String> {
+    let len = read_be_i32(buf, pos)?;
+    let len = usize::try_from(len).ok()?;
+    let s = std::str::from_utf8(buf.get(*pos..*pos + len)?).ok()?.to_string();
+    *pos += len;
+    Some(s)
 }
 
-#[no_mangle]
-pub extern "C" fn lk_connect_with_role(
-    client: *mut LkClientHandle,
-    url: *const c_char,
-    token: *const c_char,
-    role: LkRole,
-) -> LkResult {
-    if client.is_null() {
-        return err(1, "client null");
+/// # Safety
+/// `frame.device_id`/`frame.subject_name` must be valid NUL-terminated
+/// strings, and `frame.blendshapes` must point to at least
+/// `frame.blendshape_count` readable `f32`s.
+unsafe fn encode_face_frame(frame: &LkFaceFrame) -> Result<Vec<u8>> {
+    let device_id = cstr(frame.device_id)?;
+    let subject_name = cstr(frame.subject_name)?;
+    if frame.blendshape_count > 0 && frame.blendshapes.is_null() {
+        anyhow::bail!("blendshapes null");
     }
+    let blendshapes = if frame.blendshape_count == 0 {
+        &[][..]
+    } else {
+        std::slice::from_raw_parts(frame.blendshapes, frame.blendshape_count)
+    };
 
-    let url = unsafe { match cstr(url) {
-        Ok(s) => s.to_string(),
-        Err(e) => return err(2, &e.to_string()),
-    }};
-    let token = unsafe { match cstr(token) {
-        Ok(s) => s.to_string(),
-        Err(e) => return err(2, &e.to_string()),
-    }};
+    let mut buf = Vec::with_capacity(32 + device_id.len() + subject_name.len() + blendshapes.len() * 4);
+    buf.extend_from_slice(&FACE_PROTOCOL_VERSION.to_be_bytes());
+    write_be_string(&mut buf, device_id);
+    write_be_string(&mut buf, subject_name);
+    buf.extend_from_slice(&frame.frame_number.to_be_bytes());
+    buf.extend_from_slice(&frame.subframe.to_be_bytes());
+    buf.extend_from_slice(&frame.frame_rate_num.to_be_bytes());
+    buf.extend_from_slice(&frame.frame_rate_den.to_be_bytes());
+    buf.extend_from_slice(&(blendshapes.len() as i32).to_be_bytes());
+    for v in blendshapes {
+        buf.extend_from_slice(&v.to_be_bytes());
+    }
+    Ok(buf)
+}
 
-    let c = unsafe { &*(client as *const Client) };
-    let mut g = c.0.lock().unwrap();
-    let rt = g.rt.clone();
+fn decode_face_frame(buf: &[u8]) -> Option<DecodedFaceFrame> {
+    let mut pos = 0;
+    if read_be_i32(buf, &mut pos)? != FACE_PROTOCOL_VERSION {
+        return None;
+    }
+    let device_id = read_be_string(buf, &mut pos)?;
+    let subject_name = read_be_string(buf, &mut pos)?;
+    let frame_number = read_be_i32(buf, &mut pos)?;
+    let subframe = read_be_i32(buf, &mut pos)?;
+    let frame_rate_num = read_be_i32(buf, &mut pos)?;
+    let frame_rate_den = read_be_i32(buf, &mut pos)?;
+    let count = usize::try_from(read_be_i32(buf, &mut pos)?).ok()?;
+    // Bounds-check against the remaining buffer before allocating so a
+    // corrupt/hostile count can't drive an oversized allocation.
+    buf.get(pos..pos.checked_add(count.checked_mul(4)?)?)?;
+    let mut blendshapes = Vec::with_capacity(count);
+    for _ in 0..count {
+        blendshapes.push(f32::from_be_bytes(buf.get(pos..pos + 4)?.try_into().ok()?));
+        pos += 4;
+    }
+    Some(DecodedFaceFrame {
+        device_id: CString::new(device_id).unwrap_or_default(),
+        subject_name: CString::new(subject_name).unwrap_or_default(),
+        frame_number,
+        subframe,
+        frame_rate_num,
+        frame_rate_den,
+        blendshapes,
+    })
+}
 
-    let role_copy = role; // copy enum (Copy)
-    let res = rt.block_on(async move {
-        let mut opts = RoomOptions::default();
-        // If explicit Publisher, disable auto_subscribe to avoid subscribing to media.
-        if matches!(role_copy, LkRole::Publisher) { opts.auto_subscribe = false; }
-        let (room, events) = Room::connect(&url, &token, opts).await?;
-        Ok::<(Room, tokio::sync::mpsc::UnboundedReceiver<RoomEvent>), anyhow::Error>((room, events))
-    });
+#[cfg(test)]
+mod face_frame_codec_tests {
+    use super::*;
 
-    match res {
-        Ok((room, mut events)) => {
-            g.role = role_copy;
-            let client_arc = c.0.clone();
-            lk_log!(g, LkLogLevel::Info, "Connected. role={:?} auto_subscribe={}", role_copy, !matches!(role_copy, LkRole::Publisher));
-            
-            // Notify connection established
-            if let Some((cb, user)) = g.connection_cb.as_ref() {
-                cb(user.0, LkConnectionState::Connected, 0, ptr::null());
-            }
-            
-            // Spawn event processor to handle incoming data/audio
-            g.rt.spawn(async move {
-                while let Some(ev) = events.recv().await {
-                    match ev {
-                        RoomEvent::ByteStreamOpened { reader, topic: _, participant_identity: _ } => {
-                            let Some(reader) = reader.take() else { continue; };
-                            // Read all bytes, then invoke callback if set
-                            let bytes_res = reader.read_all().await;
-                            if let Ok(content) = bytes_res {
-                                // Copy to Vec to ensure stable backing memory for callback
-                                let buf: Vec<u8> = content.to_vec();
-                                lk_log_arc!(client_arc, LkLogLevel::Debug, "ByteStreamOpened: received {} bytes", buf.len());
-                                let guard_opt = client_arc.lock().ok();
-                                if let Some(guard) = guard_opt {
-                                    if let Some((cb, user)) = guard.data_cb.as_ref() {
-                                        // SAFETY: We call user-provided callback synchronously
-                                        cb(user.0, buf.as_ptr(), buf.len());
-                                    }
-                                }
-                                drop(buf);
-                            }
-                        }
-                        RoomEvent::Disconnected { reason } => {
-                            lk_log_arc!(client_arc, LkLogLevel::Info, "Disconnected event: reason={:?}", reason);
-                            let guard_opt = client_arc.lock().ok();
-                            if let Some(guard) = guard_opt {
-                                if let Some((cb, user)) = guard.connection_cb.as_ref() {
-                                    let msg = CString::new(format!("{:?}", reason)).unwrap_or_default();
-                                    cb(user.0, LkConnectionState::Disconnected, 0, msg.as_ptr());
-                                }
-                            }
-                        }
-                        RoomEvent::ConnectionStateChanged(state) => {
-                            lk_log_arc!(client_arc, LkLogLevel::Debug, "ConnectionStateChanged: {:?}", state);
-                            let guard_opt = client_arc.lock().ok();
-                            if let Some(guard) = guard_opt {
-                                if let Some((cb, user)) = guard.connection_cb.as_ref() {
-                                    let lk_state = match state {
-                                        livekit::ConnectionState::Disconnected => LkConnectionState::Disconnected,
-                                        livekit::ConnectionState::Connected => LkConnectionState::Connected,
-                                        livekit::ConnectionState::Reconnecting => LkConnectionState::Reconnecting,
-                                    };
-                                    cb(user.0, lk_state, 0, ptr::null());
-                                }
-                            }
-                        }
-                        RoomEvent::TrackSubscribed { track, publication, participant: _ } => {
-                            // Remote audio subscribed - set up a NativeAudioStream and forward frames to audio callback
-                            if let RemoteTrack::Audio(audio) = track {
-                                lk_log_arc!(client_arc, LkLogLevel::Info, "TrackSubscribed audio: name='{}', sid='{}'", publication.name(), publication.sid());
-                                // Extract underlying RTC track to build a stream reader
-                                let rtc = audio.rtc_track();
-                                let client_arc2 = client_arc.clone();
-                                
-                                // Use configured audio output format
-                                let (sample_rate, channels) = {
-                                    let guard_opt = client_arc.lock().ok();
-                                    if let Some(guard) = guard_opt {
-                                        (guard.audio_output_format.sample_rate as u32, guard.audio_output_format.channels as u32)
-                                    } else {
-                                        (48_000u32, 1u32)
-                                    }
-                                };
-                                
-                                // Spawn a task to poll audio frames and invoke the user callback synchronously per frame
-                                tokio::spawn(async move {
-                                    let mut stream = NativeAudioStream::new(rtc, sample_rate as i32, channels as i32);
-                                    let mut logged_first = false;
-                                    while let Some(frame) = stream.next().await {
-                                        // Copy to Vec to ensure stable memory for callback
-                                        let buf: Vec<i16> = frame.data.as_ref().to_vec();
-
-                                        if let Ok(guard) = client_arc2.lock() {
-                                            if let Some((cb, user)) = guard.audio_cb.as_ref() {
-                                                let frames_per_channel = frame.samples_per_channel as usize;
-                                                let ch = frame.num_channels as c_int;
-                                                let sr = frame.sample_rate as c_int;
-                                                cb(user.0, buf.as_ptr(), frames_per_channel, ch, sr);
-                                            }
-                                        }
-                                        // buf drops after callback returns
+    #[test]
+    fn round_trips_through_encode_decode() {
+        let device_id = CString::new("device-1").unwrap();
+        let subject_name = CString::new("subject-1").unwrap();
+        let blendshapes = vec![0.0f32, 0.25, 0.5, 0.75, 1.0];
+        let frame = LkFaceFrame {
+            device_id: device_id.as_ptr(),
+            subject_name: subject_name.as_ptr(),
+            frame_number: 42,
+            subframe: 1,
+            frame_rate_num: 60,
+            frame_rate_den: 1,
+            blendshapes: blendshapes.as_ptr(),
+            blendshape_count: blendshapes.len(),
+        };
+        let encoded = unsafe { encode_face_frame(&frame) }.unwrap();
+        let decoded = decode_face_frame(&encoded).expect("round trip should decode");
+        assert_eq!(decoded.device_id.as_c_str(), device_id.as_c_str());
+        assert_eq!(decoded.subject_name.as_c_str(), subject_name.as_c_str());
+        assert_eq!(decoded.frame_number, 42);
+        assert_eq!(decoded.subframe, 1);
+        assert_eq!(decoded.frame_rate_num, 60);
+        assert_eq!(decoded.frame_rate_den, 1);
+        assert_eq!(decoded.blendshapes, blendshapes);
+    }
 
-                                        if !logged_first {
-                                            lk_log_arc!(client_arc2, LkLogLevel::Debug, "First remote audio frame: sr={}Hz, ch={}, fpc={}", frame.sample_rate, frame.num_channels, frame.samples_per_channel);
-                                            logged_first = true;
-                                        }
-                                    }
-                                });
-                            }
-                        }
-                        other => {
-                            // Trace level catch-all
-                            lk_log_arc!(client_arc, LkLogLevel::Trace, "Event: {:?}", other);
-                        }
-                    }
-                }
-            });
-            g.room = Some(room);
-            ok()
-        }
-        Err(e) => err(3, &format!("connect failed: {e}")),
+    #[test]
+    fn round_trips_with_zero_blendshapes() {
+        let device_id = CString::new("device-2").unwrap();
+        let subject_name = CString::new("subject-2").unwrap();
+        let frame = LkFaceFrame {
+            device_id: device_id.as_ptr(),
+            subject_name: subject_name.as_ptr(),
+            frame_number: 0,
+            subframe: 0,
+            frame_rate_num: 30,
+            frame_rate_den: 1,
+            blendshapes: std::ptr::null(),
+            blendshape_count: 0,
+        };
+        let encoded = unsafe { encode_face_frame(&frame) }.unwrap();
+        let decoded = decode_face_frame(&encoded).expect("round trip should decode");
+        assert!(decoded.blendshapes.is_empty());
+    }
+
+    #[test]
+    fn rejects_wrong_protocol_version() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&(FACE_PROTOCOL_VERSION + 1).to_be_bytes());
+        assert!(decode_face_frame(&buf).is_none());
+    }
+
+    #[test]
+    fn rejects_truncated_buffer() {
+        let device_id = CString::new("d").unwrap();
+        let subject_name = CString::new("s").unwrap();
+        let blendshapes = vec![1.0f32; 4];
+        let frame = LkFaceFrame {
+            device_id: device_id.as_ptr(),
+            subject_name: subject_name.as_ptr(),
+            frame_number: 1,
+            subframe: 0,
+            frame_rate_num: 30,
+            frame_rate_den: 1,
+            blendshapes: blendshapes.as_ptr(),
+            blendshape_count: blendshapes.len(),
+        };
+        let encoded = unsafe { encode_face_frame(&frame) }.unwrap();
+        assert!(decode_face_frame(&encoded[..encoded.len() - 2]).is_none());
+    }
+
+    #[test]
+    fn rejects_hostile_blendshape_count_without_allocating() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&FACE_PROTOCOL_VERSION.to_be_bytes());
+        write_be_string(&mut buf, "d");
+        write_be_string(&mut buf, "s");
+        buf.extend_from_slice(&0i32.to_be_bytes()); // frame_number
+        buf.extend_from_slice(&0i32.to_be_bytes()); // subframe
+        buf.extend_from_slice(&30i32.to_be_bytes()); // frame_rate_num
+        buf.extend_from_slice(&1i32.to_be_bytes()); // frame_rate_den
+        buf.extend_from_slice(&i32::MAX.to_be_bytes()); // hostile blendshape count
+        assert!(decode_face_frame(&buf).is_none());
     }
 }
 
-#[no_mangle]
-pub extern "C" fn lk_connect_async(
-    client: *mut LkClientHandle,
-    url: *const c_char,
-    token: *const c_char,
-) -> LkResult {
-    // Default to Both
-    lk_connect_with_role_async(client, url, token, LkRole::Both)
+async fn send_face_frame(room: &Room, buf: &[u8]) -> Result<()> {
+    let options = StreamByteOptions { topic: FACE_TOPIC.to_string(), ..Default::default() };
+    let writer: ByteStreamWriter = room.local_participant().stream_bytes(options).await?;
+    writer.write(buf).await?;
+    writer.close().await?;
+    Ok(())
 }
 
-#[no_mangle]
-pub extern "C" fn lk_connect_with_role_async(
-    client: *mut LkClientHandle,
-    url: *const c_char,
-    token: *const c_char,
-    role: LkRole,
-) -> LkResult {
-    if client.is_null() {
-        return err(1, "client null");
+/// Parses a completed `ByteStreamOpened` transfer on `FACE_TOPIC` and, if it
+/// decodes cleanly, hands it to `face_cb`. Malformed frames (wrong version,
+/// truncated) are dropped and logged rather than passed on - unlike RPC
+/// frames there's no sender waiting on a reply to fail.
+async fn handle_face_frame(state: Arc<Mutex<ClientState>>, buf: Vec<u8>) {
+    let Some(decoded) = decode_face_frame(&buf) else {
+        lk_log_arc!(state, LkLogLevel::Warn, LkLogCategory::Data, "dropped malformed face frame ({} bytes)", buf.len());
+        return;
+    };
+    let g = state.lock().unwrap();
+    if let Some((cb, user)) = g.face_cb.as_ref() {
+        let frame = LkFaceFrame {
+            device_id: decoded.device_id.as_ptr(),
+            subject_name: decoded.subject_name.as_ptr(),
+            frame_number: decoded.frame_number,
+            subframe: decoded.subframe,
+            frame_rate_num: decoded.frame_rate_num,
+            frame_rate_den: decoded.frame_rate_den,
+            blendshapes: decoded.blendshapes.as_ptr(),
+            blendshape_count: decoded.blendshapes.len(),
+        };
+        cb(user.0, &frame);
     }
+}
 
-    let url = unsafe { match cstr(url) {
-        Ok(s) => s.to_string(),
-        Err(e) => return err(2, &e.to_string()),
-    }};
-    let token = unsafe { match cstr(token) {
-        Ok(s) => s.to_string(),
-        Err(e) => return err(2, &e.to_string()),
-    }};
+// --------- GIF thumbnail capture (subscribed video preview) ---------
 
-    let c = unsafe { &*(client as *const Client) };
-    let client_arc = c.0.clone();
+#[repr(C)]
+pub struct LkBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
 
-    // Early-out if already connected
-    if let Ok(g) = client_arc.lock() {
-        if g.room.is_some() {
-            return err(104, "already connected");
+struct GifFrame {
+    rgba: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+/// Ring of recently decoded frames for one subscribed track, downscaled and
+/// throttled to `fps` as they arrive so `lk_finish_gif_capture` only ever has
+/// to encode, never resample. Oldest frame is dropped once `max_frames` is
+/// reached rather than growing unbounded, so a forgotten capture just caps at
+/// a few seconds of video instead of eating memory.
+struct GifCapture {
+    track_sid: String,
+    fps: u32,
+    max_frames: usize,
+    max_dimension: u32,
+    frame_interval: Duration,
+    last_captured_at: Option<std::time::Instant>,
+    frames: VecDeque<GifFrame>,
+}
+
+impl GifCapture {
+    fn push_frame(&mut self, rgba: &[u8], width: u32, height: u32) {
+        if let Some(last) = self.last_captured_at {
+            if last.elapsed() < self.frame_interval {
+                return;
+            }
         }
-        // Notify connecting state if callback present
-        if let Some((cb, user)) = g.connection_cb.as_ref() {
-            cb(user.0, LkConnectionState::Connecting, 0, ptr::null());
+        self.last_captured_at = Some(std::time::Instant::now());
+        let (w, h, scaled) = downscale_rgba(rgba, width, height, self.max_dimension);
+        if self.frames.len() >= self.max_frames {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(GifFrame { rgba: scaled, width: w, height: h });
+    }
+}
+
+struct GifCaptureHandleRef {
+    client: Arc<Mutex<ClientState>>,
+    capture_id: u64,
+}
+
+#[repr(C)]
+pub struct LkGifCaptureHandle(GifCaptureHandleRef);
+
+// Nearest-neighbor downscale so the longer side is at most `max_dimension`;
+// a no-op if the frame already fits, since capture is for small HUD/editor
+// previews, not archival quality.
+fn downscale_rgba(rgba: &[u8], width: u32, height: u32, max_dimension: u32) -> (u32, u32, Vec<u8>) {
+    if max_dimension == 0 || (width <= max_dimension && height <= max_dimension) {
+        return (width, height, rgba.to_vec());
+    }
+    let scale = max_dimension as f64 / width.max(height) as f64;
+    let out_w = ((width as f64 * scale).round() as u32).max(1);
+    let out_h = ((height as f64 * scale).round() as u32).max(1);
+    let mut out = vec![0u8; (out_w * out_h * 4) as usize];
+    for y in 0..out_h {
+        let src_y = ((y as f64 / scale) as u32).min(height - 1);
+        for x in 0..out_w {
+            let src_x = ((x as f64 / scale) as u32).min(width - 1);
+            let src_idx = ((src_y * width + src_x) * 4) as usize;
+            let dst_idx = ((y * out_w + x) * 4) as usize;
+            out[dst_idx..dst_idx + 4].copy_from_slice(&rgba[src_idx..src_idx + 4]);
+        }
+    }
+    (out_w, out_h, out)
+}
+
+// 4x4 Bayer matrix; each frame quantizes against a small per-pixel brightness
+// bias drawn from this table, phase-shifted by frame index. That keeps dither
+// noise from being identical on every frame (which reads as a fixed grain
+// pattern sitting on top of the motion) while still breaking up banding in
+// the small, shared palette.
+const BAYER_4X4: [[i16; 4]; 4] = [
+    [0, 8, 2, 10],
+    [12, 4, 14, 6],
+    [3, 11, 1, 9],
+    [15, 7, 13, 5],
+];
+
+fn dither_quantize(quant: &NeuQuant, frame: &GifFrame, frame_index: usize) -> Vec<u8> {
+    let phase_row = frame_index % 4;
+    let mut indices = Vec::with_capacity((frame.width * frame.height) as usize);
+    for y in 0..frame.height {
+        let row = &BAYER_4X4[(y as usize + phase_row) % 4];
+        for x in 0..frame.width {
+            let idx = ((y * frame.width + x) * 4) as usize;
+            let bias = (row[(x as usize) % 4] - 8) * 2;
+            let mut px = [0u8; 4];
+            for (ch, out) in px.iter_mut().take(3).enumerate() {
+                *out = (frame.rgba[idx + ch] as i16 + bias).clamp(0, 255) as u8;
+            }
+            px[3] = frame.rgba[idx + 3];
+            indices.push(quant.index_of(&px) as u8);
+        }
+    }
+    indices
+}
+
+/// Builds one shared ("cross-frame") palette from samples taken across every
+/// buffered frame, then encodes each against it with a per-frame dither
+/// phase. A single global palette (rather than per-frame NeuQuant, the
+/// `gif` crate's default) is what keeps color assignment stable from frame
+/// to frame, so a near-static scene doesn't flicker between two slightly
+/// different quantizations of the same color.
+fn encode_gif(frames: &[GifFrame], fps: u32) -> Result<Vec<u8>> {
+    anyhow::ensure!(!frames.is_empty(), "no frames captured");
+    let (width, height) = (frames[0].width, frames[0].height);
+
+    let mut sample: Vec<u8> = Vec::new();
+    for frame in frames {
+        let total_pixels = (frame.width * frame.height) as usize;
+        let stride = (total_pixels / 5_000).max(1);
+        for px in (0..total_pixels).step_by(stride) {
+            sample.extend_from_slice(&frame.rgba[px * 4..px * 4 + 4]);
+        }
+    }
+    let quant = NeuQuant::new(10, 256, &sample);
+    let palette = quant.color_map_rgb();
+
+    let mut buf = Vec::new();
+    {
+        let mut encoder = Encoder::new(&mut buf, width as u16, height as u16, &palette)?;
+        encoder.set_repeat(Repeat::Infinite)?;
+        let delay_cs = (100 / fps.max(1)).max(1) as u16;
+        for (i, frame) in frames.iter().enumerate() {
+            let indices = dither_quantize(&quant, frame, i);
+            let mut gif_frame = Frame::from_indexed_pixels(frame.width as u16, frame.height as u16, &indices, None);
+            gif_frame.delay = delay_cs;
+            encoder.write_frame(&gif_frame)?;
+        }
+    }
+    Ok(buf)
+}
+
+// A running device-capture thread and the flag used to ask it to stop. The
+// cpal `Stream` itself lives on the thread (cpal streams aren't `Send`), so
+// stopping it means flipping `stop` and joining the thread that drops it.
+struct CaptureThread {
+    stop: Arc<AtomicBool>,
+    handle: std::thread::JoinHandle<()>,
+}
+
+impl CaptureThread {
+    fn stop_and_join(self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = self.handle.join();
+    }
+}
+
+struct ClientState {
+    room: Option<Room>,
+    audio_tracks: HashMap<u64, AudioPipeline>,
+    default_audio_track_id: Option<u64>,
+    next_audio_track_id: u64,
+    video_tracks: HashMap<u64, VideoPipeline>,
+    next_video_track_id: u64,
+    rt: Arc<Runtime>,
+
+    // Callbacks
+    data_cb: Option<(extern "C" fn(*mut c_void, *const u8, usize), UserPtr)>,
+    data_cb_ex: Option<(extern "C" fn(*mut c_void, *const c_char, LkReliability, *const u8, usize), UserPtr)>,
+    // audio_cb / audio_cb_ex live in HotState (lock-free ArcSwapOption), not here -
+    // see `Client.1`. They used to be plain fields guarded by this mutex, which
+    // meant every remote-audio frame took the full ClientState lock just to read
+    // a function pointer.
+    audio_format_change_cb: Option<(extern "C" fn(*mut c_void, c_int, c_int), UserPtr)>,
+    connection_cb: Option<(extern "C" fn(*mut c_void, LkConnectionState, c_int, *const c_char), UserPtr)>,
+    video_cb: Option<(extern "C" fn(*mut c_void, *const u8, c_int, c_int, c_int, LkVideoFormat, i64), UserPtr)>,
+    video_format_change_cb: Option<(extern "C" fn(*mut c_void, c_int, c_int, LkVideoFormat), UserPtr)>,
+    active_speakers_cb: Option<(extern "C" fn(*mut c_void, *const *const c_char, *const c_float, usize), UserPtr)>,
+    track_muted_cb: Option<(extern "C" fn(*mut c_void, *const c_char, *const c_char, c_int), UserPtr)>,
+    face_cb: Option<(extern "C" fn(*mut c_void, *const LkFaceFrame), UserPtr)>,
+    // Fired from the RemoteTrack::Video arms of TrackSubscribed/TrackUnsubscribed,
+    // keyed by (publisher identity, track sid) like track_muted_cb.
+    video_track_subscribed_cb: Option<(extern "C" fn(*mut c_void, *const c_char, *const c_char), UserPtr)>,
+    video_track_unsubscribed_cb: Option<(extern "C" fn(*mut c_void, *const c_char, *const c_char), UserPtr)>,
+    /// Fired from `lk_set_audio_output_format` whenever the negotiated format actually changes.
+    audio_format_changed_cb: Option<(extern "C" fn(*mut c_void, LkSampleFormat, c_int, c_int), UserPtr)>,
+
+    // Per-participant remote audio stream tasks, keyed by track SID, so a
+    // TrackUnsubscribed can tear down exactly the matching NativeAudioStream loop.
+    remote_audio_streams: HashMap<String, JoinHandle<()>>,
+    // Mirrors remote_audio_streams for subscribed remote video tracks.
+    remote_video_streams: HashMap<String, JoinHandle<()>>,
+
+    // Active GIF thumbnail captures, keyed by an ID handed out as
+    // LkGifCaptureHandle; each remote video frame loop above checks this map
+    // for a capture matching its own track SID and, if found, feeds it a
+    // (throttled, downscaled) copy of the decoded frame.
+    gif_captures: HashMap<u64, Arc<Mutex<GifCapture>>>,
+    next_gif_capture_id: u64,
+
+    // Inbound byte streams (file/asset transfers), keyed by an ID we assign
+    // on ByteStreamOpened so lk_stream_abort can cancel a specific transfer.
+    incoming_streams: HashMap<u64, StreamState>,
+    next_stream_id: u64,
+    stream_cb: Option<StreamCallbacks>,
+
+    // RPC calls in flight, keyed by request_id; polled for expiry by the
+    // timer task spawned in lk_client_create. Handlers registered via
+    // lk_rpc_register_handler, keyed by method name.
+    rpc_pending: HashMap<u64, RpcPendingCall>,
+    rpc_handlers: HashMap<String, RpcHandlerEntry>,
+    next_rpc_id: u64,
+    // Single sink for inbound Event frames; unlike `rpc_handlers` these aren't
+    // per-method and never get a Response frame sent back.
+    rpc_event_cb: Option<(RpcEventFn, UserPtr)>,
+
+    // Checked by every remote audio stream task before invoking audio_cb/audio_cb_ex,
+    // including tracks subscribed after the flag was set.
+    deafened: Arc<AtomicBool>,
+    // Checked by every AudioPipeline consumer worker before popping the ring and
+    // calling capture_frame, so un-muting resumes instantly with no buffered samples lost.
+    mic_muted: Arc<AtomicBool>,
+
+    // Owns the OS thread driving an optional cpal input stream; kept around so the
+    // `cpal::Stream` isn't dropped (and torn down) out from under the capture thread.
+    // Each entry carries its own stop flag so `lk_stop_capture`/`lk_client_destroy`
+    // can signal the thread to tear down its `cpal::Stream` and return, instead of
+    // leaking it for the process lifetime.
+    capture_threads: Vec<CaptureThread>,
+
+    // Configuration
+    role: LkRole,
+    audio_publish_opts: AudioPublishOptions,
+    audio_output_format: AudioOutputFormat,
+    data_labels: DataLabels,
+    log_level: LkLogLevel,
+    // EWMA time constant (tau) for spawn_stats_accumulator's smoothed rates;
+    // doesn't change the accumulator's tick rate, just how fast it reacts.
+    stats_window_ms: i32,
+
+    // In-memory ring of recorded log lines, drained by `lk_log_drain`. Bounded
+    // by LOG_RING_BUDGET_BYTES (FIFO eviction) rather than by record count, since
+    // message length varies a lot between e.g. connection and per-frame logs.
+    log_records: VecDeque<LogRecord>,
+    log_records_bytes: usize,
+    log_category_mask: i32,
+
+    // Statistics
+    data_stats: Arc<DataStatsCounters>,
+}
+
+type AudioCb = (extern "C" fn(*mut c_void, *const i16, usize, c_int, c_int), UserPtr);
+type AudioCbEx = (
+    extern "C" fn(*mut c_void, *const c_char, *const c_char, *const i16, usize, c_int, c_int),
+    UserPtr,
+);
+
+// Function pointers read on the real-time remote-audio thread every frame.
+// These live outside the ClientState mutex entirely so that a TrackSubscribed
+// stream task never contends with the UE thread (or anything else) just to
+// read a callback pointer.
+struct HotState {
+    audio_cb: ArcSwapOption<AudioCb>,
+    audio_cb_ex: ArcSwapOption<AudioCbEx>,
+}
+
+impl Default for HotState {
+    fn default() -> Self {
+        Self {
+            audio_cb: ArcSwapOption::empty(),
+            audio_cb_ex: ArcSwapOption::empty(),
+        }
+    }
+}
+
+// Commands that mutate ClientState off of the calling (UE) thread. An owning
+// actor task drains this channel and applies each command under the mutex,
+// so the FFI entry point for a hot per-frame call (publishing captured PCM)
+// never blocks on the lock itself - it pushes directly only when `try_lock`
+// succeeds and otherwise just enqueues.
+enum Command {
+    PushAudioPcm { pcm: Vec<i16>, channels: u32, sample_rate: u32 },
+}
+
+fn spawn_command_actor(state: Arc<Mutex<ClientState>>) -> mpsc::UnboundedSender<Command> {
+    let (tx, mut rx) = mpsc::unbounded_channel::<Command>();
+    let rt = state.lock().unwrap().rt.clone();
+    rt.spawn(async move {
+        while let Some(cmd) = rx.recv().await {
+            match cmd {
+                Command::PushAudioPcm { pcm, channels, sample_rate } => {
+                    let mut g = state.lock().unwrap();
+                    if g.room.is_none() {
+                        lk_log!(g, LkLogLevel::Error, LkLogCategory::Audio, "audio push dropped: not connected");
+                        continue;
+                    }
+                    let track_id = match ensure_default_audio_track(&mut g, sample_rate, channels) {
+                        Ok(id) => id,
+                        Err(e) => {
+                            lk_log!(g, LkLogLevel::Error, LkLogCategory::Audio, "audio pipeline init failed: {}", e);
+                            continue;
+                        }
+                    };
+                    if let Some(pipeline) = g.audio_tracks.get_mut(&track_id) {
+                        if let Err(e) = pipeline.push(&pcm, sample_rate) {
+                            lk_log!(g, LkLogLevel::Error, LkLogCategory::Audio, "audio ring push failed: {}", e);
+                        }
+                    }
+                }
+            }
+        }
+    });
+    tx
+}
+
+/// Fails any `lk_rpc_call` past its deadline with a timeout status. Holds
+/// only a `Weak` ref to `ClientState` so this task doesn't keep a destroyed
+/// client's state alive - it exits once the last strong ref is dropped.
+fn spawn_rpc_timeout_task(state: &Arc<Mutex<ClientState>>) {
+    let weak = Arc::downgrade(state);
+    let rt = state.lock().unwrap().rt.clone();
+    rt.spawn(async move {
+        let mut tick = interval(Duration::from_millis(250));
+        loop {
+            tick.tick().await;
+            let Some(state) = weak.upgrade() else { break; };
+            let mut g = state.lock().unwrap();
+            let now = std::time::Instant::now();
+            let expired: Vec<u64> = g
+                .rpc_pending
+                .iter()
+                .filter(|(_, p)| p.deadline <= now)
+                .map(|(id, _)| *id)
+                .collect();
+            for id in expired {
+                if let Some(p) = g.rpc_pending.remove(&id) {
+                    (p.reply_cb)(p.user.0, id, RPC_STATUS_TIMEOUT, ptr::null(), 0);
+                }
+            }
+        }
+    });
+}
+
+/// Periodically turns `DataStatsCounters`'s cumulative atomics into the
+/// windowed rates `lk_get_data_stats` reports, so callers don't have to poll
+/// and difference the raw counters themselves. Ticks on a fixed short period
+/// and sizes each sample's EWMA smoothing to the actual elapsed time, so
+/// `stats_window_ms` is just the smoothing time constant - changing it
+/// doesn't change how often this wakes up.
+fn spawn_stats_accumulator(state: &Arc<Mutex<ClientState>>) {
+    const TICK: Duration = Duration::from_millis(500);
+    let weak = Arc::downgrade(state);
+    let rt = state.lock().unwrap().rt.clone();
+    rt.spawn(async move {
+        let mut tick = interval(TICK);
+        let mut last_tick = std::time::Instant::now();
+        loop {
+            tick.tick().await;
+            let Some(state) = weak.upgrade() else { break; };
+            let now = std::time::Instant::now();
+            let dt = now.duration_since(last_tick).as_secs_f64().max(0.001);
+            last_tick = now;
+
+            let g = state.lock().unwrap();
+            let stats = g.data_stats.clone();
+            let window_secs = (g.stats_window_ms.max(1) as f64) / 1000.0;
+            drop(g);
+
+            let reliable_bytes = stats.reliable_sent_bytes.load(Ordering::Relaxed);
+            let lossy_bytes = stats.lossy_sent_bytes.load(Ordering::Relaxed);
+            let reliable_msgs = stats.reliable_msgs_sent.load(Ordering::Relaxed);
+
+            let d_reliable_bytes = (reliable_bytes - stats.last_reliable_bytes.swap(reliable_bytes, Ordering::Relaxed)) as f64;
+            let d_lossy_bytes = (lossy_bytes - stats.last_lossy_bytes.swap(lossy_bytes, Ordering::Relaxed)) as f64;
+            let d_reliable_msgs = (reliable_msgs - stats.last_reliable_msgs.swap(reliable_msgs, Ordering::Relaxed)) as f64;
+
+            // alpha = 1 - e^(-dt/tau): the fraction of the gap to the new
+            // sample we close this tick, given tau = stats_window_ms.
+            let alpha = 1.0 - (-dt / window_secs).exp();
+            ewma_update(&stats.reliable_bps, d_reliable_bytes / dt, alpha);
+            ewma_update(&stats.lossy_bps, d_lossy_bytes / dt, alpha);
+            ewma_update(&stats.reliable_msgs_per_sec, d_reliable_msgs / dt, alpha);
+        }
+    });
+}
+
+async fn send_rpc_frame(room: &Room, buf: &[u8]) -> Result<()> {
+    let options = StreamByteOptions { topic: RPC_TOPIC.to_string(), ..Default::default() };
+    let writer: ByteStreamWriter = room.local_participant().stream_bytes(options).await?;
+    writer.write(buf).await?;
+    writer.close().await?;
+    Ok(())
+}
+
+/// Parses a completed `ByteStreamOpened` transfer on `RPC_TOPIC`: resolves a
+/// pending `lk_rpc_call` for a Response frame, or runs the matching
+/// `lk_rpc_register_handler` and sends its reply for a Request frame.
+async fn handle_rpc_frame(state: Arc<Mutex<ClientState>>, buf: Vec<u8>) {
+    let Some(frame) = decode_rpc_frame(&buf) else {
+        return;
+    };
+    match frame.kind {
+        RPC_KIND_RESPONSE => {
+            let pending = state.lock().unwrap().rpc_pending.remove(&frame.request_id);
+            if let Some(p) = pending {
+                let status = match frame.status as c_int {
+                    RPC_STATUS_OK => RPC_STATUS_OK,
+                    RPC_STATUS_NO_HANDLER => RPC_STATUS_NO_HANDLER,
+                    _ => RPC_STATUS_HANDLER_ERROR,
+                };
+                (p.reply_cb)(p.user.0, frame.request_id, status, frame.payload.as_ptr(), frame.payload.len());
+            }
+        }
+        RPC_KIND_REQUEST => {
+            let handler = state
+                .lock()
+                .unwrap()
+                .rpc_handlers
+                .get(frame.method)
+                .map(|h| (h.handler, UserPtr(h.user.0)));
+            let (status, response): (u8, Vec<u8>) = match handler {
+                Some((handler_fn, user)) => {
+                    let method_c = CString::new(frame.method).unwrap_or_default();
+                    let mut out_buf = vec![0u8; RPC_MAX_PAYLOAD];
+                    let mut out_len: usize = 0;
+                    let code = handler_fn(
+                        user.0,
+                        method_c.as_ptr(),
+                        frame.payload.as_ptr(),
+                        frame.payload.len(),
+                        out_buf.as_mut_ptr(),
+                        out_buf.len(),
+                        &mut out_len,
+                    );
+                    if code == 0 {
+                        out_buf.truncate(out_len.min(RPC_MAX_PAYLOAD));
+                        (0, out_buf)
+                    } else {
+                        (RPC_STATUS_HANDLER_ERROR as u8, Vec::new())
+                    }
+                }
+                None => (RPC_STATUS_NO_HANDLER as u8, Vec::new()),
+            };
+            let response_buf = encode_rpc_response(frame.request_id, status, &response);
+            let room = state.lock().unwrap().room.clone();
+            if let Some(room) = room {
+                if let Err(e) = send_rpc_frame(&room, &response_buf).await {
+                    lk_log_arc!(state, LkLogLevel::Error, LkLogCategory::Data, "rpc response send failed: {}", e);
+                }
+            }
+        }
+        RPC_KIND_EVENT => {
+            let cb = state.lock().unwrap().rpc_event_cb.as_ref().map(|(f, u)| (*f, u.0));
+            if let Some((event_cb, user)) = cb {
+                let method_c = CString::new(frame.method).unwrap_or_default();
+                event_cb(user, method_c.as_ptr(), frame.payload.as_ptr(), frame.payload.len());
+            }
+        }
+        _ => {}
+    }
+}
+
+struct Client(Arc<Mutex<ClientState>>, Arc<HotState>, mpsc::UnboundedSender<Command>);
+
+static RT: OnceCell<Arc<Runtime>> = OnceCell::new();
+fn runtime() -> Arc<Runtime> {
+    RT.get_or_init(|| Arc::new(Runtime::new().expect("tokio runtime"))).clone()
+}
+
+fn fire_active_speakers(guard: &ClientState, speakers: &[RemoteParticipant]) {
+    if let Some((cb, user)) = guard.active_speakers_cb.as_ref() {
+        let levels: Vec<c_float> = speakers.iter().map(|p| p.audio_level() as c_float).collect();
+        let cstrings: Vec<CString> = speakers
+            .iter()
+            .map(|p| CString::new(p.identity().to_string()).unwrap_or_default())
+            .collect();
+        let ptrs: Vec<*const c_char> = cstrings.iter().map(|c| c.as_ptr()).collect();
+        cb(user.0, ptrs.as_ptr(), levels.as_ptr(), ptrs.len());
+        // cstrings/ptrs drop after the callback returns
+    }
+}
+
+fn fire_track_muted(guard: &ClientState, identity: &str, track_sid: &str, muted: bool) {
+    if let Some((cb, user)) = guard.track_muted_cb.as_ref() {
+        let identity_c = CString::new(identity).unwrap_or_default();
+        let sid_c = CString::new(track_sid).unwrap_or_default();
+        cb(user.0, identity_c.as_ptr(), sid_c.as_ptr(), muted as c_int);
+    }
+}
+
+fn fire_video_track_subscribed(guard: &ClientState, identity: &str, track_sid: &str) {
+    if let Some((cb, user)) = guard.video_track_subscribed_cb.as_ref() {
+        let identity_c = CString::new(identity).unwrap_or_default();
+        let sid_c = CString::new(track_sid).unwrap_or_default();
+        cb(user.0, identity_c.as_ptr(), sid_c.as_ptr());
+    }
+}
+
+fn fire_audio_format_changed(guard: &ClientState, format: LkSampleFormat, sample_rate: c_int, channels: c_int) {
+    if let Some((cb, user)) = guard.audio_format_changed_cb.as_ref() {
+        cb(user.0, format, sample_rate, channels);
+    }
+}
+
+fn fire_video_track_unsubscribed(guard: &ClientState, identity: &str, track_sid: &str) {
+    if let Some((cb, user)) = guard.video_track_unsubscribed_cb.as_ref() {
+        let identity_c = CString::new(identity).unwrap_or_default();
+        let sid_c = CString::new(track_sid).unwrap_or_default();
+        cb(user.0, identity_c.as_ptr(), sid_c.as_ptr());
+    }
+}
+
+unsafe fn cstr<'a>(p: *const c_char) -> Result<&'a str> {
+    if p.is_null() {
+        anyhow::bail!("null pointer")
+    }
+    Ok(CStr::from_ptr(p).to_str()?)
+}
+
+// --------- FFI functions ---------
+
+#[no_mangle]
+pub extern "C" fn lk_client_create() -> *mut LkClientHandle {
+    let state = ClientState {
+        room: None,
+        audio_tracks: HashMap::new(),
+        default_audio_track_id: None,
+        next_audio_track_id: 1,
+        video_tracks: HashMap::new(),
+        next_video_track_id: 1,
+        rt: runtime(),
+        data_cb: None,
+        data_cb_ex: None,
+        audio_format_change_cb: None,
+        connection_cb: None,
+        video_cb: None,
+        video_format_change_cb: None,
+        active_speakers_cb: None,
+        track_muted_cb: None,
+        face_cb: None,
+        video_track_subscribed_cb: None,
+        video_track_unsubscribed_cb: None,
+        audio_format_changed_cb: None,
+        remote_audio_streams: HashMap::new(),
+        remote_video_streams: HashMap::new(),
+        gif_captures: HashMap::new(),
+        next_gif_capture_id: 1,
+        incoming_streams: HashMap::new(),
+        next_stream_id: 1,
+        stream_cb: None,
+        rpc_pending: HashMap::new(),
+        rpc_handlers: HashMap::new(),
+        next_rpc_id: 1,
+        rpc_event_cb: None,
+        deafened: Arc::new(AtomicBool::new(false)),
+        mic_muted: Arc::new(AtomicBool::new(false)),
+        capture_threads: Vec::new(),
+        role: LkRole::Both,
+        audio_publish_opts: AudioPublishOptions::default(),
+        audio_output_format: AudioOutputFormat::default(),
+        data_labels: DataLabels::default(),
+        log_level: LkLogLevel::Error,
+        stats_window_ms: 2_000,
+        log_records: VecDeque::new(),
+        log_records_bytes: 0,
+        log_category_mask: 0b1111,
+        data_stats: Arc::new(DataStatsCounters::default()),
+    };
+    let state = Arc::new(Mutex::new(state));
+    let cmd_tx = spawn_command_actor(state.clone());
+    spawn_rpc_timeout_task(&state);
+    spawn_stats_accumulator(&state);
+    let boxed = Box::new(Client(state, Arc::new(HotState::default()), cmd_tx));
+    Box::into_raw(boxed) as *mut LkClientHandle
+}
+
+#[no_mangle]
+pub extern "C" fn lk_client_destroy(client: *mut LkClientHandle) {
+    if client.is_null() {
+        return;
+    }
+    let boxed = unsafe { Box::from_raw(client as *mut Client) };
+    let threads = boxed.0.lock().unwrap().capture_threads.drain(..).collect::<Vec<_>>();
+    for t in threads {
+        t.stop_and_join();
+    }
+    drop(boxed);
+}
+
+#[no_mangle]
+pub extern "C" fn lk_client_set_data_callback(
+    client: *mut LkClientHandle,
+    cb: Option<extern "C" fn(user: *mut c_void, bytes: *const u8, len: usize)>,
+    user: *mut c_void,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.data_cb = cb.map(|f| (f, UserPtr(user)));
+    ok()
+}
+
+#[no_mangle]
+pub extern "C" fn lk_client_set_audio_callback(
+    client: *mut LkClientHandle,
+    cb: Option<extern "C" fn(user: *mut c_void, pcm: *const i16, frames_per_channel: usize, channels: c_int, sample_rate: c_int)>,
+    user: *mut c_void,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    c.1.audio_cb.store(cb.map(|f| Arc::new((f, UserPtr(user)))));
+    ok()
+}
+
+/// Like `lk_client_set_audio_callback`, but also reports the originating
+/// participant identity and track SID alongside each frame so a host can
+/// route remote speakers to distinct in-world emitters.
+#[no_mangle]
+pub extern "C" fn lk_client_set_audio_callback_ex(
+    client: *mut LkClientHandle,
+    cb: Option<extern "C" fn(user: *mut c_void, identity: *const c_char, track_sid: *const c_char, pcm: *const i16, frames_per_channel: usize, channels: c_int, sample_rate: c_int)>,
+    user: *mut c_void,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    c.1.audio_cb_ex.store(cb.map(|f| Arc::new((f, UserPtr(user)))));
+    ok()
+}
+
+/// Delivers decoded remote video frames as a single packed RGBA buffer
+/// (`format` is always `LkVideoFormat::Rgba`, `stride` is `width * 4`).
+#[no_mangle]
+pub extern "C" fn lk_client_set_video_callback(
+    client: *mut LkClientHandle,
+    cb: Option<extern "C" fn(user: *mut c_void, buf: *const u8, width: c_int, height: c_int, stride: c_int, format: LkVideoFormat, timestamp_us: i64)>,
+    user: *mut c_void,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.video_cb = cb.map(|f| (f, UserPtr(user)));
+    ok()
+}
+
+#[no_mangle]
+pub extern "C" fn lk_set_video_format_change_callback(
+    client: *mut LkClientHandle,
+    cb: Option<extern "C" fn(user: *mut c_void, width: c_int, height: c_int, format: LkVideoFormat)>,
+    user: *mut c_void,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.video_format_change_cb = cb.map(|f| (f, UserPtr(user)));
+    ok()
+}
+
+/// Fires on `RoomEvent::ActiveSpeakersChanged` with a packed array of
+/// participant identities and their per-speaker audio levels, mirroring the
+/// zed live_kit_client backend's `on_active_speakers_changed` event. The
+/// backing `CString`/pointer arrays stay alive for the duration of the call only.
+#[no_mangle]
+pub extern "C" fn lk_set_active_speakers_callback(
+    client: *mut LkClientHandle,
+    cb: Option<extern "C" fn(user: *mut c_void, identities: *const *const c_char, levels: *const c_float, count: usize)>,
+    user: *mut c_void,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.active_speakers_cb = cb.map(|f| (f, UserPtr(user)));
+    ok()
+}
+
+/// Fires on `RoomEvent::TrackMuted`/`TrackUnmuted` for any remote track.
+#[no_mangle]
+pub extern "C" fn lk_set_track_muted_callback(
+    client: *mut LkClientHandle,
+    cb: Option<extern "C" fn(user: *mut c_void, identity: *const c_char, track_sid: *const c_char, muted: c_int)>,
+    user: *mut c_void,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.track_muted_cb = cb.map(|f| (f, UserPtr(user)));
+    ok()
+}
+
+/// Fires on `RoomEvent::TrackSubscribed` for a remote video track, keyed by
+/// the publishing participant's identity and the track's SID.
+#[no_mangle]
+pub extern "C" fn lk_set_video_track_subscribed_callback(
+    client: *mut LkClientHandle,
+    cb: Option<extern "C" fn(user: *mut c_void, identity: *const c_char, track_sid: *const c_char)>,
+    user: *mut c_void,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.video_track_subscribed_cb = cb.map(|f| (f, UserPtr(user)));
+    ok()
+}
+
+/// Fires on `RoomEvent::TrackUnsubscribed` for a remote video track, keyed by
+/// the publishing participant's identity and the track's SID.
+#[no_mangle]
+pub extern "C" fn lk_set_video_track_unsubscribed_callback(
+    client: *mut LkClientHandle,
+    cb: Option<extern "C" fn(user: *mut c_void, identity: *const c_char, track_sid: *const c_char)>,
+    user: *mut c_void,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.video_track_unsubscribed_cb = cb.map(|f| (f, UserPtr(user)));
+    ok()
+}
+
+/// Fires once per decoded Live Link Face frame received on the `lk-face`
+/// byte-stream topic. `frame` (and everything it points to) is only valid
+/// for the duration of the call.
+#[no_mangle]
+pub extern "C" fn lk_set_face_frame_callback(
+    client: *mut LkClientHandle,
+    cb: Option<extern "C" fn(user: *mut c_void, frame: *const LkFaceFrame)>,
+    user: *mut c_void,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.face_cb = cb.map(|f| (f, UserPtr(user)));
+    ok()
+}
+
+/// Registers streaming delivery for inbound byte streams (e.g. file/asset
+/// transfers) as an alternative to `data_cb`, which only fires once a whole
+/// stream has been buffered in memory. `open_cb` reports `total_length` as
+/// -1 when the sender didn't advertise a size. `chunk_cb` may be called from
+/// a different stream's task concurrently with another, but never
+/// concurrently for the same `stream_id`. `close_cb` reports `error_code` 0
+/// on a clean, contiguous transfer and a nonzero code with a gap/abort
+/// message otherwise.
+#[no_mangle]
+pub extern "C" fn lk_set_stream_cb(
+    client: *mut LkClientHandle,
+    open_cb: Option<extern "C" fn(user: *mut c_void, stream_id: u64, topic: *const c_char, identity: *const c_char, total_length: i64)>,
+    chunk_cb: Option<extern "C" fn(user: *mut c_void, stream_id: u64, offset: u64, ptr: *const u8, len: usize)>,
+    close_cb: Option<extern "C" fn(user: *mut c_void, stream_id: u64, error_code: c_int, message: *const c_char)>,
+    user: *mut c_void,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.stream_cb = match (open_cb, chunk_cb, close_cb) {
+        (Some(open), Some(chunk), Some(close)) => Some(StreamCallbacks { open, chunk, close, user: UserPtr(user) }),
+        _ => None,
+    };
+    ok()
+}
+
+/// Cancels an in-progress inbound byte stream started by `ByteStreamOpened`.
+/// The stream's `close_cb` is fired with error code 499 so the host can tell
+/// an abort apart from a clean completion or a mid-transfer gap.
+#[no_mangle]
+pub extern "C" fn lk_stream_abort(client: *mut LkClientHandle, stream_id: u64) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    match g.incoming_streams.remove(&stream_id) {
+        Some(stream) => {
+            stream.handle.abort();
+            if let Some(cbs) = g.stream_cb.as_ref() {
+                let msg = CString::new("aborted by host").unwrap_or_default();
+                (cbs.close)(cbs.user.0, stream_id, 499, msg.as_ptr());
+            }
+            ok()
+        }
+        None => err(9, "stream not found"),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn lk_client_set_data_callback_ex(
+    client: *mut LkClientHandle,
+    cb: Option<extern "C" fn(user: *mut c_void, label: *const c_char, reliability: LkReliability, bytes: *const u8, len: usize)>,
+    user: *mut c_void,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.data_cb_ex = cb.map(|f| (f, UserPtr(user)));
+    ok()
+}
+
+#[no_mangle]
+pub extern "C" fn lk_set_audio_format_change_callback(
+    client: *mut LkClientHandle,
+    cb: Option<extern "C" fn(user: *mut c_void, sample_rate: c_int, channels: c_int)>,
+    user: *mut c_void,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.audio_format_change_cb = cb.map(|f| (f, UserPtr(user)));
+    ok()
+}
+
+#[no_mangle]
+pub extern "C" fn lk_set_connection_callback(
+    client: *mut LkClientHandle,
+    cb: Option<extern "C" fn(user: *mut c_void, state: LkConnectionState, reason_code: c_int, message: *const c_char)>,
+    user: *mut c_void,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.connection_cb = cb.map(|f| (f, UserPtr(user)));
+    ok()
+}
+
+// --------- Configuration Functions ---------
+
+#[no_mangle]
+pub extern "C" fn lk_set_audio_publish_options(
+    client: *mut LkClientHandle,
+    bitrate_bps: c_int,
+    enable_dtx: c_int,
+    stereo: c_int,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.audio_publish_opts = AudioPublishOptions {
+        bitrate_bps,
+        enable_dtx: enable_dtx != 0,
+        stereo: stereo != 0,
+    };
+    lk_log!(g, LkLogLevel::Debug, LkLogCategory::Audio, "Audio publish options set: bitrate={}bps, dtx={}, stereo={}", bitrate_bps, enable_dtx != 0, stereo != 0);
+    ok()
+}
+
+#[no_mangle]
+pub extern "C" fn lk_set_audio_output_format(
+    client: *mut LkClientHandle,
+    sample_rate: c_int,
+    channels: c_int,
+) -> LkResult {
+    // Delegate to lk_set_audio_output_format_ex with the format this
+    // function always assumed before _ex existed, so already-compiled
+    // callers of this signature keep working unchanged.
+    lk_set_audio_output_format_ex(client, sample_rate, channels, LkSampleFormat::S16LE)
+}
+
+/// Same as `lk_set_audio_output_format` but lets the caller also name the
+/// sample format (S16LE or Float32LE). The format is stored and reported
+/// through `lk_set_audio_format_changed_callback`; remote audio is still
+/// delivered as interleaved i16 through the audio callbacks whichever format
+/// is set. Added as an `_ex` variant instead
+/// of changing `lk_set_audio_output_format`'s signature, matching the
+/// `lk_client_set_audio_callback`/`_ex` and `lk_send_data`/`_ex` convention,
+/// so already-compiled callers of the original 3-arg function keep working.
+#[no_mangle]
+pub extern "C" fn lk_set_audio_output_format_ex(
+    client: *mut LkClientHandle,
+    sample_rate: c_int,
+    channels: c_int,
+    format: LkSampleFormat,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    if sample_rate <= 0 || channels <= 0 {
+        return err(5, "invalid audio output format");
+    }
+    if format != LkSampleFormat::S16LE && format != LkSampleFormat::Float32LE {
+        return err(7, "unsupported sample format: only S16LE/Float32LE are supported on this platform");
+    }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    let changed = g.audio_output_format.sample_rate != sample_rate
+        || g.audio_output_format.channels != channels
+        || g.audio_output_format.format != format;
+    g.audio_output_format = AudioOutputFormat {
+        sample_rate,
+        channels,
+        format,
+    };
+    lk_log!(g, LkLogLevel::Debug, LkLogCategory::Audio, "Audio output format set: sr={}Hz, ch={}, fmt={:?}", sample_rate, channels, format);
+    if changed {
+        fire_audio_format_changed(&g, format, sample_rate, channels);
+    }
+    ok()
+}
+
+/// Fires whenever `lk_set_audio_output_format` actually changes the negotiated
+/// sample rate, channel count, or sample format. The reported format is the
+/// one the host set; it does not change the i16 PCM the audio callbacks deliver.
+#[no_mangle]
+pub extern "C" fn lk_set_audio_format_changed_callback(
+    client: *mut LkClientHandle,
+    cb: Option<extern "C" fn(user: *mut c_void, format: LkSampleFormat, sample_rate: c_int, channels: c_int)>,
+    user: *mut c_void,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.audio_format_changed_cb = cb.map(|f| (f, UserPtr(user)));
+    ok()
+}
+
+#[no_mangle]
+pub extern "C" fn lk_set_default_data_labels(
+    client: *mut LkClientHandle,
+    reliable_label: *const c_char,
+    lossy_label: *const c_char,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    
+    if !reliable_label.is_null() {
+        if let Ok(s) = unsafe { cstr(reliable_label) } {
+            g.data_labels.reliable = s.to_string();
+        }
+    }
+    if !lossy_label.is_null() {
+        if let Ok(s) = unsafe { cstr(lossy_label) } {
+            g.data_labels.lossy = s.to_string();
+        }
+    }
+    
+    lk_log!(g, LkLogLevel::Debug, LkLogCategory::Data, "Data labels set: reliable='{}', lossy='{}'", g.data_labels.reliable, g.data_labels.lossy);
+    ok()
+}
+
+/// Sets the EWMA time constant for the windowed rates in `lk_get_data_stats`
+/// (`reliable_bps`/`lossy_bps`/`reliable_msgs_per_sec`). Larger values react
+/// more slowly to bursts but report a steadier number.
+#[no_mangle]
+pub extern "C" fn lk_set_stats_window_ms(
+    client: *mut LkClientHandle,
+    window_ms: c_int,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    if window_ms <= 0 { return err(5, "window_ms must be positive"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.stats_window_ms = window_ms;
+    ok()
+}
+
+#[no_mangle]
+pub extern "C" fn lk_set_reconnect_backoff(
+    client: *mut LkClientHandle,
+    _initial_ms: c_int,
+    _max_ms: c_int,
+    _multiplier: c_float,
+) -> LkResult {
+    // Note: LiveKit SDK manages reconnection internally; this is a placeholder
+    // for future implementation if SDK exposes these controls
+    if !client.is_null() {
+        let c = unsafe { &*(client as *const Client) };
+        if let Ok(mut g) = c.0.lock() {
+            lk_log!(g, LkLogLevel::Trace, LkLogCategory::Connection, "Reconnect backoff configuration requested (not yet implemented)");
+        }
+    }
+    ok()
+}
+
+#[no_mangle]
+pub extern "C" fn lk_refresh_token(
+    _client: *mut LkClientHandle,
+    _token: *const c_char,
+) -> LkResult {
+    // Note: Token refresh at runtime is not currently supported by LiveKit SDK
+    // Best practice is to disconnect and reconnect with new token
+    err(501, "Token refresh not supported; use disconnect + reconnect")
+}
+
+#[no_mangle]
+pub extern "C" fn lk_set_role(
+    _client: *mut LkClientHandle,
+    _role: LkRole,
+    _auto_subscribe: c_int,
+) -> LkResult {
+    // Note: Dynamic role switching without reconnect is not currently supported
+    // Best practice is to disconnect and reconnect with new role
+    err(501, "Dynamic role switching not supported; use disconnect + reconnect with new role")
+}
+
+/// Pauses delivery from every active remote audio stream, including tracks
+/// subscribed after deafen was enabled, without tearing down subscriptions.
+#[no_mangle]
+pub extern "C" fn lk_set_deafened(client: *mut LkClientHandle, deafened: c_int) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.deafened.store(deafened != 0, Ordering::Relaxed);
+    lk_log!(g, LkLogLevel::Debug, LkLogCategory::Audio, "Deafened set to {}", deafened != 0);
+    ok()
+}
+
+/// Stops every published AudioPipeline from consuming its ring buffer and
+/// feeding the local track, while leaving queued samples in place so
+/// un-muting resumes instantly.
+#[no_mangle]
+pub extern "C" fn lk_set_microphone_muted(client: *mut LkClientHandle, muted: c_int) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.mic_muted.store(muted != 0, Ordering::Relaxed);
+    lk_log!(g, LkLogLevel::Debug, LkLogCategory::Audio, "Microphone muted set to {}", muted != 0);
+    ok()
+}
+
+#[no_mangle]
+pub extern "C" fn lk_set_log_level(
+    client: *mut LkClientHandle,
+    level: LkLogLevel,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.log_level = level;
+    lk_log!(g, LkLogLevel::Debug, LkLogCategory::Connection, "Log level set to: {:?}", level);
+    ok()
+}
+
+/// Sets both the minimum severity and the category bitmask used to decide which
+/// lines get recorded into the ring (and, for now, printed). `category_mask` is
+/// `1 << LkLogCategory value`; pass `-1` to leave all categories enabled.
+#[no_mangle]
+pub extern "C" fn lk_log_set_filter(
+    client: *mut LkClientHandle,
+    min_level: LkLogLevel,
+    category_mask: c_int,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.log_level = min_level;
+    g.log_category_mask = category_mask;
+    ok()
+}
+
+/// Pops up to `max` of the oldest recorded log lines into `out_records`, oldest
+/// first, and writes the number actually written to `*out_count`. Each
+/// `LkLogRecord::message` is a `CString::into_raw` pointer the caller must
+/// free with `lk_free_str`.
+///
+/// # Safety
+/// `out_records` must point to an array of at least `max` `LkLogRecord`s, and
+/// `out_count` must point to a valid `usize`.
+#[no_mangle]
+pub unsafe extern "C" fn lk_log_drain(
+    client: *mut LkClientHandle,
+    out_records: *mut LkLogRecord,
+    max: usize,
+    out_count: *mut usize,
+) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    if out_records.is_null() || out_count.is_null() { return err(2, "null output pointer"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    let mut n = 0usize;
+    while n < max {
+        let rec = match g.log_records.pop_front() {
+            Some(rec) => rec,
+            None => break,
+        };
+        g.log_records_bytes -= rec.message.len() + std::mem::size_of::<LogRecord>();
+        let message = CString::new(rec.message).unwrap_or_default().into_raw();
+        unsafe {
+            *out_records.add(n) = LkLogRecord {
+                timestamp_ns: rec.timestamp_ns,
+                level: rec.level,
+                category: rec.category,
+                message,
+            };
+        }
+        n += 1;
+    }
+    unsafe { *out_count = n; }
+    ok()
+}
+
+/// Drops every line currently held in the log ring without draining it.
+#[no_mangle]
+pub extern "C" fn lk_log_clear(client: *mut LkClientHandle) -> LkResult {
+    if client.is_null() { return err(1, "client null"); }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.log_records.clear();
+    g.log_records_bytes = 0;
+    ok()
+}
+
+// --------- Connection Functions ---------
+
+#[no_mangle]
+pub extern "C" fn lk_connect(
+    client: *mut LkClientHandle,
+    url: *const c_char,
+    token: *const c_char,
+) -> LkResult {
+    // Default to Both
+    lk_connect_with_role(client, url, token, LkRole::Both)
+}
+
+#[no_mangle]
+pub extern "C" fn lk_connect_with_role(
+    client: *mut LkClientHandle,
+    url: *const c_char,
+    token: *const c_char,
+    role: LkRole,
+) -> LkResult {
+    if client.is_null() {
+        return err(1, "client null");
+    }
+
+    let url = unsafe { match cstr(url) {
+        Ok(s) => s.to_string(),
+        Err(e) => return err(2, &e.to_string()),
+    }};
+    let token = unsafe { match cstr(token) {
+        Ok(s) => s.to_string(),
+        Err(e) => return err(2, &e.to_string()),
+    }};
+
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    let rt = g.rt.clone();
+
+    let role_copy = role; // copy enum (Copy)
+    let res = rt.block_on(async move {
+        let mut opts = RoomOptions::default();
+        // If explicit Publisher, disable auto_subscribe to avoid subscribing to media.
+        if matches!(role_copy, LkRole::Publisher) { opts.auto_subscribe = false; }
+        let (room, events) = Room::connect(&url, &token, opts).await?;
+        Ok::<(Room, tokio::sync::mpsc::UnboundedReceiver<RoomEvent>), anyhow::Error>((room, events))
+    });
+
+    match res {
+        Ok((room, mut events)) => {
+            g.role = role_copy;
+            let client_arc = c.0.clone();
+            let hot = c.1.clone();
+            lk_log!(g, LkLogLevel::Info, LkLogCategory::Connection, "Connected. role={:?} auto_subscribe={}", role_copy, !matches!(role_copy, LkRole::Publisher));
+            
+            // Notify connection established
+            if let Some((cb, user)) = g.connection_cb.as_ref() {
+                cb(user.0, LkConnectionState::Connected, 0, ptr::null());
+            }
+            
+            // Spawn event processor to handle incoming data/audio
+            g.rt.spawn(async move {
+                while let Some(ev) = events.recv().await {
+                    match ev {
+                        RoomEvent::ByteStreamOpened { reader, topic, participant_identity } => {
+                            let Some(mut reader) = reader.take() else { continue; };
+                            let total_length = reader.info().total_length;
+                            let stream_id = {
+                                let mut g = client_arc.lock().unwrap();
+                                let id = g.next_stream_id;
+                                g.next_stream_id += 1;
+                                id
+                            };
+                            lk_log_arc!(client_arc, LkLogLevel::Debug, LkLogCategory::Data, "ByteStreamOpened: stream_id={}, topic='{}'", stream_id, topic);
+                            let is_rpc = topic == RPC_TOPIC;
+                            let is_face = topic == FACE_TOPIC;
+                            let topic_c = CString::new(topic).unwrap_or_default();
+                            let identity_c = CString::new(participant_identity).unwrap_or_default();
+                            if let Ok(g) = client_arc.lock() {
+                                if let Some(cbs) = g.stream_cb.as_ref() {
+                                    (cbs.open)(cbs.user.0, stream_id, topic_c.as_ptr(), identity_c.as_ptr(), total_length.map(|v| v as i64).unwrap_or(-1));
+                                }
+                            }
+
+                            let client_arc2 = client_arc.clone();
+                            // Hold the lock until StreamState is registered so the reader
+                            // task can't record (or finish) a chunk before it exists.
+                            let mut streams_g = client_arc.lock().unwrap();
+                            let handle = tokio::spawn(async move {
+                                let mut offset: u64 = 0;
+                                let mut accumulated: Vec<u8> = Vec::new();
+                                let mut read_error = false;
+                                while let Some(chunk_res) = reader.next().await {
+                                    match chunk_res {
+                                        Ok(bytes) => {
+                                            let len = bytes.as_ref().len();
+                                            accumulated.extend_from_slice(bytes.as_ref());
+                                            if let Ok(mut g) = client_arc2.lock() {
+                                                if let Some(state) = g.incoming_streams.get_mut(&stream_id) {
+                                                    state.received.insert(offset, offset + len as u64);
+                                                }
+                                                if let Some(cbs) = g.stream_cb.as_ref() {
+                                                    (cbs.chunk)(cbs.user.0, stream_id, offset, bytes.as_ref().as_ptr(), len);
+                                                }
+                                            }
+                                            offset += len as u64;
+                                        }
+                                        Err(e) => {
+                                            read_error = true;
+                                            lk_log_arc!(client_arc2, LkLogLevel::Error, LkLogCategory::Data, "ByteStream {} read error: {}", stream_id, e);
+                                            break;
+                                        }
+                                    }
+                                }
+
+                                let mut g = client_arc2.lock().unwrap();
+                                let gap = g.incoming_streams.get(&stream_id)
+                                    .map(|s| match s.total_length {
+                                        Some(total) => !s.received.is_contiguous_from_zero(total),
+                                        None => false,
+                                    })
+                                    .unwrap_or(false);
+                                g.incoming_streams.remove(&stream_id);
+                                if !is_rpc && !is_face {
+                                    if let Some((cb, user)) = g.data_cb.as_ref() {
+                                        cb(user.0, accumulated.as_ptr(), accumulated.len());
+                                    }
+                                    if let Some(cbs) = g.stream_cb.as_ref() {
+                                        let (code, msg) = if read_error {
+                                            (1, "read error")
+                                        } else if gap {
+                                            (2, "gap in received byte ranges")
+                                        } else {
+                                            (0, "")
+                                        };
+                                        let msg_c = CString::new(msg).unwrap_or_default();
+                                        (cbs.close)(cbs.user.0, stream_id, code, msg_c.as_ptr());
+                                    }
+                                }
+                                drop(g);
+                                if is_rpc && !read_error && !gap {
+                                    handle_rpc_frame(client_arc2.clone(), accumulated).await;
+                                } else if is_face && !read_error && !gap {
+                                    handle_face_frame(client_arc2.clone(), accumulated).await;
+                                }
+                            });
+
+                            streams_g.incoming_streams.insert(stream_id, StreamState {
+                                handle,
+                                received: RangeSet::default(),
+                                total_length,
+                            });
+                            drop(streams_g);
+                        }
+                        RoomEvent::Disconnected { reason } => {
+                            lk_log_arc!(client_arc, LkLogLevel::Info, LkLogCategory::Connection, "Disconnected event: reason={:?}", reason);
+                            let guard_opt = client_arc.lock().ok();
+                            if let Some(guard) = guard_opt {
+                                if let Some((cb, user)) = guard.connection_cb.as_ref() {
+                                    let msg = CString::new(format!("{:?}", reason)).unwrap_or_default();
+                                    cb(user.0, LkConnectionState::Disconnected, 0, msg.as_ptr());
+                                }
+                            }
+                        }
+                        RoomEvent::ConnectionStateChanged(state) => {
+                            lk_log_arc!(client_arc, LkLogLevel::Debug, LkLogCategory::Connection, "ConnectionStateChanged: {:?}", state);
+                            let guard_opt = client_arc.lock().ok();
+                            if let Some(guard) = guard_opt {
+                                if let Some((cb, user)) = guard.connection_cb.as_ref() {
+                                    let lk_state = match state {
+                                        livekit::ConnectionState::Disconnected => LkConnectionState::Disconnected,
+                                        livekit::ConnectionState::Connected => LkConnectionState::Connected,
+                                        livekit::ConnectionState::Reconnecting => LkConnectionState::Reconnecting,
+                                    };
+                                    cb(user.0, lk_state, 0, ptr::null());
+                                }
+                            }
+                        }
+                        RoomEvent::TrackSubscribed { track, publication, participant } => {
+                            let sid_key = publication.sid().to_string();
+                            let identity_c = CString::new(participant.identity().to_string()).unwrap_or_default();
+                            let sid_c = CString::new(sid_key.clone()).unwrap_or_default();
+                            match track {
+                                RemoteTrack::Audio(audio) => {
+                                    // Remote audio subscribed - set up a NativeAudioStream and forward frames to audio callback
+                                    lk_log_arc!(client_arc, LkLogLevel::Info, LkLogCategory::Rtc, "TrackSubscribed audio: name='{}', sid='{}'", publication.name(), publication.sid());
+                                    // Extract underlying RTC track to build a stream reader
+                                    let rtc = audio.rtc_track();
+                                    let client_arc2 = client_arc.clone();
+                                    let hot2 = hot.clone();
+
+                                    // Use configured audio output format and the current deafen flag; the
+                                    // flag is re-read live each frame, so deafen applies to this track too
+                                    // even though it was subscribed after the button was pressed.
+                                    let (sample_rate, channels, deafened) = {
+                                        let guard_opt = client_arc.lock().ok();
+                                        if let Some(guard) = guard_opt {
+                                            (guard.audio_output_format.sample_rate as u32, guard.audio_output_format.channels as u32, guard.deafened.clone())
+                                        } else {
+                                            (48_000u32, 1u32, Arc::new(AtomicBool::new(false)))
+                                        }
+                                    };
+
+                                    // Spawn a task to poll audio frames and invoke the user callback synchronously per frame
+                                    let handle = tokio::spawn(async move {
+                                        let mut stream = NativeAudioStream::new(rtc, sample_rate as i32, channels as i32);
+                                        let mut logged_first = false;
+                                        while let Some(frame) = stream.next().await {
+                                            // Copy to Vec to ensure stable memory for callback
+                                            let buf: Vec<i16> = frame.data.as_ref().to_vec();
+
+                                            if !deafened.load(Ordering::Relaxed) {
+                                                let frames_per_channel = frame.samples_per_channel as usize;
+                                                let ch = frame.num_channels as c_int;
+                                                let sr = frame.sample_rate as c_int;
+                                                if let Some(cb) = hot2.audio_cb.load_full() {
+                                                    let (cb, user) = &*cb;
+                                                    cb(user.0, buf.as_ptr(), frames_per_channel, ch, sr);
+                                                }
+                                                if let Some(cb) = hot2.audio_cb_ex.load_full() {
+                                                    let (cb, user) = &*cb;
+                                                    cb(user.0, identity_c.as_ptr(), sid_c.as_ptr(), buf.as_ptr(), frames_per_channel, ch, sr);
+                                                }
+                                            }
+                                            // buf drops after callback returns
+
+                                            if !logged_first {
+                                                lk_log_arc!(client_arc2, LkLogLevel::Debug, LkLogCategory::Audio, "First remote audio frame: sr={}Hz, ch={}, fpc={}", frame.sample_rate, frame.num_channels, frame.samples_per_channel);
+                                                logged_first = true;
+                                            }
+                                        }
+                                    });
+                                    if let Ok(mut guard) = client_arc.lock() {
+                                        if let Some(old) = guard.remote_audio_streams.insert(sid_key, handle) {
+                                            old.abort();
+                                        }
+                                    }
+                                }
+                                RemoteTrack::Video(video) => {
+                                    lk_log_arc!(client_arc, LkLogLevel::Info, LkLogCategory::Rtc, "TrackSubscribed video: name='{}', sid='{}'", publication.name(), publication.sid());
+                                    let rtc = video.rtc_track();
+                                    let client_arc2 = client_arc.clone();
+                                    let sid_key2 = sid_key.clone();
+                                    let sid_key_capture = sid_key.clone();
+                                    let handle = tokio::spawn(async move {
+                                        let mut stream = NativeVideoStream::new(rtc);
+                                        while let Some(frame) = stream.next().await {
+                                            let width = frame.buffer.width();
+                                            let height = frame.buffer.height();
+                                            let stride = width * 4;
+                                            let mut rgba = vec![0u8; (stride * height) as usize];
+                                            let i420 = match frame.buffer.as_i420() {
+                                                Some(i420) => i420,
+                                                None => {
+                                                    lk_log_arc!(client_arc2, LkLogLevel::Warn, LkLogCategory::Rtc, "Dropping remote video frame: buffer did not decode to I420");
+                                                    continue;
+                                                }
+                                            };
+                                            let (data_y, data_u, data_v) = (i420.data_y(), i420.data_u(), i420.data_v());
+                                            yuv_helper::i420_to_abgr(
+                                                data_y, i420.stride_y(),
+                                                data_u, i420.stride_u(),
+                                                data_v, i420.stride_v(),
+                                                &mut rgba, stride,
+                                                width as i32, height as i32,
+                                            );
+                                            if let Ok(guard) = client_arc2.lock() {
+                                                if let Some((cb, user)) = guard.video_cb.as_ref() {
+                                                    cb(user.0, rgba.as_ptr(), width as c_int, height as c_int, stride as c_int, LkVideoFormat::Rgba, frame.timestamp_us);
+                                                }
+                                                for capture in guard.gif_captures.values() {
+                                                    let mut capture = capture.lock().unwrap();
+                                                    if capture.track_sid == sid_key_capture {
+                                                        capture.push_frame(&rgba, width, height);
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    });
+                                    if let Ok(mut guard) = client_arc.lock() {
+                                        if let Some(old) = guard.remote_video_streams.insert(sid_key2, handle) {
+                                            old.abort();
+                                        }
+                                        fire_video_track_subscribed(&guard, &identity_c.to_string_lossy(), &sid_key);
+                                    }
+                                }
+                            }
+                        }
+                        RoomEvent::TrackUnsubscribed { track: _, publication, participant } => {
+                            let sid_key = publication.sid().to_string();
+                            let identity = participant.identity().to_string();
+                            let removed_audio = client_arc.lock().ok().and_then(|mut g| g.remote_audio_streams.remove(&sid_key));
+                            if let Some(handle) = removed_audio {
+                                handle.abort();
+                                lk_log_arc!(client_arc, LkLogLevel::Info, LkLogCategory::Rtc, "TrackUnsubscribed: sid='{}', audio stream task aborted", sid_key);
+                            }
+                            let removed_video = client_arc.lock().ok().and_then(|mut g| g.remote_video_streams.remove(&sid_key));
+                            if let Some(handle) = removed_video {
+                                handle.abort();
+                                lk_log_arc!(client_arc, LkLogLevel::Info, LkLogCategory::Rtc, "TrackUnsubscribed: sid='{}', video stream task aborted", sid_key);
+                                if let Ok(guard) = client_arc.lock() {
+                                    fire_video_track_unsubscribed(&guard, &identity, &sid_key);
+                                }
+                            }
+                        }
+                        RoomEvent::ActiveSpeakersChanged { speakers } => {
+                            if let Ok(guard) = client_arc.lock() {
+                                fire_active_speakers(&guard, &speakers);
+                            }
+                        }
+                        RoomEvent::TrackMuted { participant, publication } => {
+                            if let Ok(guard) = client_arc.lock() {
+                                fire_track_muted(&guard, &participant.identity().to_string(), &publication.sid().to_string(), true);
+                            }
+                        }
+                        RoomEvent::TrackUnmuted { participant, publication } => {
+                            if let Ok(guard) = client_arc.lock() {
+                                fire_track_muted(&guard, &participant.identity().to_string(), &publication.sid().to_string(), false);
+                            }
+                        }
+                        other => {
+                            // Trace level catch-all
+                            lk_log_arc!(client_arc, LkLogLevel::Trace, LkLogCategory::Rtc, "Event: {:?}", other);
+                        }
+                    }
+                }
+            });
+            g.room = Some(room);
+            ok()
+        }
+        Err(e) => err(3, &format!("connect failed: {e}")),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn lk_connect_async(
+    client: *mut LkClientHandle,
+    url: *const c_char,
+    token: *const c_char,
+) -> LkResult {
+    // Default to Both
+    lk_connect_with_role_async(client, url, token, LkRole::Both)
+}
+
+#[no_mangle]
+pub extern "C" fn lk_connect_with_role_async(
+    client: *mut LkClientHandle,
+    url: *const c_char,
+    token: *const c_char,
+    role: LkRole,
+) -> LkResult {
+    if client.is_null() {
+        return err(1, "client null");
+    }
+
+    let url = unsafe { match cstr(url) {
+        Ok(s) => s.to_string(),
+        Err(e) => return err(2, &e.to_string()),
+    }};
+    let token = unsafe { match cstr(token) {
+        Ok(s) => s.to_string(),
+        Err(e) => return err(2, &e.to_string()),
+    }};
+
+    let c = unsafe { &*(client as *const Client) };
+    let client_arc = c.0.clone();
+    let hot = c.1.clone();
+
+    // Early-out if already connected
+    if let Ok(g) = client_arc.lock() {
+        if g.room.is_some() {
+            return err(104, "already connected");
+        }
+        // Notify connecting state if callback present
+        if let Some((cb, user)) = g.connection_cb.as_ref() {
+            cb(user.0, LkConnectionState::Connecting, 0, ptr::null());
         }
     }
 
@@ -771,18 +2738,99 @@ pub extern "C" fn lk_connect_with_role_async(
 
                 // Spawn event processing loop (mirrors sync connect)
                 let client_arc2 = client_arc.clone();
+                let hot2 = hot.clone();
                 runtime().spawn(async move {
                     while let Some(ev) = events.recv().await {
                         match ev {
-                            RoomEvent::ByteStreamOpened { reader, topic: _, participant_identity: _ } => {
-                                let Some(reader) = reader.take() else { continue; };
-                                if let Ok(content) = reader.read_all().await {
-                                    let buf: Vec<u8> = content.to_vec();
-                                    if let Ok(guard) = client_arc2.lock() {
-                                        lk_log!(guard, LkLogLevel::Debug, "ByteStreamOpened: received {} bytes", buf.len());
-                                        if let Some((cb, user)) = guard.data_cb.as_ref() { cb(user.0, buf.as_ptr(), buf.len()); }
+                            RoomEvent::ByteStreamOpened { reader, topic, participant_identity } => {
+                                let Some(mut reader) = reader.take() else { continue; };
+                                let total_length = reader.info().total_length;
+                                let stream_id = {
+                                    let mut g = client_arc2.lock().unwrap();
+                                    let id = g.next_stream_id;
+                                    g.next_stream_id += 1;
+                                    id
+                                };
+                                lk_log_arc!(client_arc2, LkLogLevel::Debug, LkLogCategory::Data, "ByteStreamOpened: stream_id={}, topic='{}'", stream_id, topic);
+                                let is_rpc = topic == RPC_TOPIC;
+                                let is_face = topic == FACE_TOPIC;
+                                let topic_c = CString::new(topic).unwrap_or_default();
+                                let identity_c = CString::new(participant_identity).unwrap_or_default();
+                                if let Ok(g) = client_arc2.lock() {
+                                    if let Some(cbs) = g.stream_cb.as_ref() {
+                                        (cbs.open)(cbs.user.0, stream_id, topic_c.as_ptr(), identity_c.as_ptr(), total_length.map(|v| v as i64).unwrap_or(-1));
                                     }
                                 }
+
+                                let client_arc3 = client_arc2.clone();
+                                // Hold the lock until StreamState is registered so the reader
+                                // task can't record (or finish) a chunk before it exists.
+                                let mut streams_g = client_arc2.lock().unwrap();
+                                let handle = tokio::spawn(async move {
+                                    let mut offset: u64 = 0;
+                                    let mut accumulated: Vec<u8> = Vec::new();
+                                    let mut read_error = false;
+                                    while let Some(chunk_res) = reader.next().await {
+                                        match chunk_res {
+                                            Ok(bytes) => {
+                                                let len = bytes.as_ref().len();
+                                                accumulated.extend_from_slice(bytes.as_ref());
+                                                if let Ok(mut g) = client_arc3.lock() {
+                                                    if let Some(state) = g.incoming_streams.get_mut(&stream_id) {
+                                                        state.received.insert(offset, offset + len as u64);
+                                                    }
+                                                    if let Some(cbs) = g.stream_cb.as_ref() {
+                                                        (cbs.chunk)(cbs.user.0, stream_id, offset, bytes.as_ref().as_ptr(), len);
+                                                    }
+                                                }
+                                                offset += len as u64;
+                                            }
+                                            Err(e) => {
+                                                read_error = true;
+                                                lk_log_arc!(client_arc3, LkLogLevel::Error, LkLogCategory::Data, "ByteStream {} read error: {}", stream_id, e);
+                                                break;
+                                            }
+                                        }
+                                    }
+
+                                    let mut g = client_arc3.lock().unwrap();
+                                    let gap = g.incoming_streams.get(&stream_id)
+                                        .map(|s| match s.total_length {
+                                            Some(total) => !s.received.is_contiguous_from_zero(total),
+                                            None => false,
+                                        })
+                                        .unwrap_or(false);
+                                    g.incoming_streams.remove(&stream_id);
+                                    if !is_rpc && !is_face {
+                                        if let Some((cb, user)) = g.data_cb.as_ref() {
+                                            cb(user.0, accumulated.as_ptr(), accumulated.len());
+                                        }
+                                        if let Some(cbs) = g.stream_cb.as_ref() {
+                                            let (code, msg) = if read_error {
+                                                (1, "read error")
+                                            } else if gap {
+                                                (2, "gap in received byte ranges")
+                                            } else {
+                                                (0, "")
+                                            };
+                                            let msg_c = CString::new(msg).unwrap_or_default();
+                                            (cbs.close)(cbs.user.0, stream_id, code, msg_c.as_ptr());
+                                        }
+                                    }
+                                    drop(g);
+                                    if is_rpc && !read_error && !gap {
+                                        handle_rpc_frame(client_arc3.clone(), accumulated).await;
+                                    } else if is_face && !read_error && !gap {
+                                        handle_face_frame(client_arc3.clone(), accumulated).await;
+                                    }
+                                });
+
+                                streams_g.incoming_streams.insert(stream_id, StreamState {
+                                    handle,
+                                    received: RangeSet::default(),
+                                    total_length,
+                                });
+                                drop(streams_g);
                             }
                             RoomEvent::Disconnected { reason } => {
                                 if let Ok(guard) = client_arc2.lock() {
@@ -804,34 +2852,128 @@ pub extern "C" fn lk_connect_with_role_async(
                                     }
                                 }
                             }
-                            RoomEvent::TrackSubscribed { track, publication, participant: _ } => {
+                            RoomEvent::TrackSubscribed { track, publication, participant } => {
+                                let sid_key = publication.sid().to_string();
+                                let identity_c = CString::new(participant.identity().to_string()).unwrap_or_default();
+                                let sid_c = CString::new(sid_key.clone()).unwrap_or_default();
+                                if let RemoteTrack::Video(video) = &track {
+                                    lk_log_arc!(client_arc2, LkLogLevel::Info, LkLogCategory::Rtc, "TrackSubscribed video: name='{}', sid='{}'", publication.name(), publication.sid());
+                                    let rtc = video.rtc_track();
+                                    let client_arc3 = client_arc2.clone();
+                                    let sid_key2 = sid_key.clone();
+                                    let sid_key_capture = sid_key.clone();
+                                    let handle = tokio::spawn(async move {
+                                        let mut stream = NativeVideoStream::new(rtc);
+                                        while let Some(frame) = stream.next().await {
+                                            let width = frame.buffer.width();
+                                            let height = frame.buffer.height();
+                                            let stride = width * 4;
+                                            let mut rgba = vec![0u8; (stride * height) as usize];
+                                            let i420 = match frame.buffer.as_i420() {
+                                                Some(i420) => i420,
+                                                None => {
+                                                    lk_log_arc!(client_arc3, LkLogLevel::Warn, LkLogCategory::Rtc, "Dropping remote video frame: buffer did not decode to I420");
+                                                    continue;
+                                                }
+                                            };
+                                            let (data_y, data_u, data_v) = (i420.data_y(), i420.data_u(), i420.data_v());
+                                            yuv_helper::i420_to_abgr(
+                                                data_y, i420.stride_y(),
+                                                data_u, i420.stride_u(),
+                                                data_v, i420.stride_v(),
+                                                &mut rgba, stride,
+                                                width as i32, height as i32,
+                                            );
+                                            if let Ok(guard) = client_arc3.lock() {
+                                                if let Some((cb, user)) = guard.video_cb.as_ref() {
+                                                    cb(user.0, rgba.as_ptr(), width as c_int, height as c_int, stride as c_int, LkVideoFormat::Rgba, frame.timestamp_us);
+                                                }
+                                                for capture in guard.gif_captures.values() {
+                                                    let mut capture = capture.lock().unwrap();
+                                                    if capture.track_sid == sid_key_capture {
+                                                        capture.push_frame(&rgba, width, height);
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    });
+                                    if let Ok(mut guard) = client_arc2.lock() {
+                                        if let Some(old) = guard.remote_video_streams.insert(sid_key2, handle) {
+                                            old.abort();
+                                        }
+                                        fire_video_track_subscribed(&guard, &identity_c.to_string_lossy(), &sid_key);
+                                    }
+                                }
                                 if let RemoteTrack::Audio(audio) = track {
-                                    lk_log_arc!(client_arc2, LkLogLevel::Info, "TrackSubscribed audio: name='{}', sid='{}'", publication.name(), publication.sid());
+                                    lk_log_arc!(client_arc2, LkLogLevel::Info, LkLogCategory::Rtc, "TrackSubscribed audio: name='{}', sid='{}'", publication.name(), publication.sid());
                                     let rtc = audio.rtc_track();
                                     let client_arc3 = client_arc2.clone();
-                                    let (sample_rate, channels) = if let Ok(guard) = client_arc2.lock() { (guard.audio_output_format.sample_rate as u32, guard.audio_output_format.channels as u32) } else { (48_000u32, 1u32) };
-                                    tokio::spawn(async move {
+                                    let hot3 = hot2.clone();
+                                    let (sample_rate, channels, deafened) = if let Ok(guard) = client_arc2.lock() { (guard.audio_output_format.sample_rate as u32, guard.audio_output_format.channels as u32, guard.deafened.clone()) } else { (48_000u32, 1u32, Arc::new(AtomicBool::new(false))) };
+                                    let handle = tokio::spawn(async move {
                                         let mut stream = NativeAudioStream::new(rtc, sample_rate as i32, channels as i32);
                                         let mut logged_first = false;
                                         while let Some(frame) = stream.next().await {
                                             let buf: Vec<i16> = frame.data.as_ref().to_vec();
-                                            if let Ok(guard) = client_arc3.lock() {
-                                                if let Some((cb, user)) = guard.audio_cb.as_ref() {
-                                                    let frames_per_channel = frame.samples_per_channel as usize;
-                                                    let ch = frame.num_channels as c_int;
-                                                    let sr = frame.sample_rate as c_int;
+                                            if !deafened.load(Ordering::Relaxed) {
+                                                let frames_per_channel = frame.samples_per_channel as usize;
+                                                let ch = frame.num_channels as c_int;
+                                                let sr = frame.sample_rate as c_int;
+                                                if let Some(cb) = hot3.audio_cb.load_full() {
+                                                    let (cb, user) = &*cb;
                                                     cb(user.0, buf.as_ptr(), frames_per_channel, ch, sr);
                                                 }
+                                                if let Some(cb) = hot3.audio_cb_ex.load_full() {
+                                                    let (cb, user) = &*cb;
+                                                    cb(user.0, identity_c.as_ptr(), sid_c.as_ptr(), buf.as_ptr(), frames_per_channel, ch, sr);
+                                                }
                                             }
                                             if !logged_first {
-                                                lk_log_arc!(client_arc3, LkLogLevel::Debug, "First remote audio frame: sr={}Hz, ch={}, fpc={}", frame.sample_rate, frame.num_channels, frame.samples_per_channel);
+                                                lk_log_arc!(client_arc3, LkLogLevel::Debug, LkLogCategory::Audio, "First remote audio frame: sr={}Hz, ch={}, fpc={}", frame.sample_rate, frame.num_channels, frame.samples_per_channel);
                                                 logged_first = true;
                                             }
                                         }
                                     });
+                                    if let Ok(mut guard) = client_arc2.lock() {
+                                        if let Some(old) = guard.remote_audio_streams.insert(sid_key, handle) {
+                                            old.abort();
+                                        }
+                                    }
+                                }
+                            }
+                            RoomEvent::TrackUnsubscribed { track: _, publication, participant } => {
+                                let sid_key = publication.sid().to_string();
+                                let identity = participant.identity().to_string();
+                                let removed_audio = client_arc2.lock().ok().and_then(|mut g| g.remote_audio_streams.remove(&sid_key));
+                                if let Some(handle) = removed_audio {
+                                    handle.abort();
+                                    lk_log_arc!(client_arc2, LkLogLevel::Info, LkLogCategory::Rtc, "TrackUnsubscribed: sid='{}', audio stream task aborted", sid_key);
+                                }
+                                let removed_video = client_arc2.lock().ok().and_then(|mut g| g.remote_video_streams.remove(&sid_key));
+                                if let Some(handle) = removed_video {
+                                    handle.abort();
+                                    lk_log_arc!(client_arc2, LkLogLevel::Info, LkLogCategory::Rtc, "TrackUnsubscribed: sid='{}', video stream task aborted", sid_key);
+                                    if let Ok(guard) = client_arc2.lock() {
+                                        fire_video_track_unsubscribed(&guard, &identity, &sid_key);
+                                    }
+                                }
+                            }
+                            RoomEvent::ActiveSpeakersChanged { speakers } => {
+                                if let Ok(guard) = client_arc2.lock() {
+                                    fire_active_speakers(&guard, &speakers);
+                                }
+                            }
+                            RoomEvent::TrackMuted { participant, publication } => {
+                                if let Ok(guard) = client_arc2.lock() {
+                                    fire_track_muted(&guard, &participant.identity().to_string(), &publication.sid().to_string(), true);
+                                }
+                            }
+                            RoomEvent::TrackUnmuted { participant, publication } => {
+                                if let Ok(guard) = client_arc2.lock() {
+                                    fire_track_muted(&guard, &participant.identity().to_string(), &publication.sid().to_string(), false);
                                 }
                             }
-                            other => { lk_log_arc!(client_arc2, LkLogLevel::Trace, "Event: {:?}", other); }
+                            other => { lk_log_arc!(client_arc2, LkLogLevel::Trace, LkLogCategory::Rtc, "Event: {:?}", other); }
                         }
                     }
                 });
@@ -845,102 +2987,970 @@ pub extern "C" fn lk_connect_with_role_async(
                 }
             }
         }
-    });
+    });
+
+    ok()
+}
+#[no_mangle]
+pub extern "C" fn lk_disconnect(client: *mut LkClientHandle) -> LkResult {
+    if client.is_null() {
+        return err(1, "client null");
+    }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+
+    if let Some(room) = g.room.take() {
+        let rt = g.rt.clone();
+        let _ = rt.block_on(async move {
+            let _ = room.close().await; // graceful shutdown
+        });
+    }
+    lk_log!(g, LkLogLevel::Info, LkLogCategory::Connection, "Disconnected");
+    g.audio_tracks.clear();
+    g.default_audio_track_id = None;
+    g.video_tracks.clear();
+    for (_, handle) in g.remote_audio_streams.drain() {
+        handle.abort();
+    }
+    for (_, handle) in g.remote_video_streams.drain() {
+        handle.abort();
+    }
+    g.gif_captures.clear();
+    ok()
+}
+
+#[no_mangle]
+pub extern "C" fn lk_client_is_ready(client: *mut LkClientHandle) -> c_int {
+    if client.is_null() {
+        return 0;
+    }
+    let c = unsafe { &*(client as *const Client) };
+    let g = c.0.lock().unwrap();
+    if g.room.is_some() { 1 } else { 0 }
+}
+
+fn next_audio_track_id(g: &mut ClientState) -> u64 {
+    let id = g.next_audio_track_id;
+    g.next_audio_track_id = g.next_audio_track_id.wrapping_add(1);
+    if g.next_audio_track_id == 0 {
+        g.next_audio_track_id = 1;
+    }
+    id
+}
+
+fn register_audio_pipeline(
+    g: &mut ClientState,
+    label: &str,
+    sample_rate: u32,
+    channels: u32,
+    buffer_ms: u32,
+) -> Result<u64> {
+    let id = next_audio_track_id(g);
+    let pipeline = create_audio_pipeline(g, label, sample_rate, channels, buffer_ms)?;
+    g.audio_tracks.insert(id, pipeline);
+    Ok(id)
+}
+
+fn ensure_default_audio_track(g: &mut ClientState, sample_rate: u32, channels: u32) -> Result<u64> {
+    if let Some(id) = g.default_audio_track_id {
+        if let Some(pipeline) = g.audio_tracks.get(&id) {
+            // Sample rate mismatches no longer hard-fail: AudioPipeline::push
+            // resamples to the pipeline's own rate. Channel count still must
+            // match, since the resampler doesn't remap channels.
+            if pipeline.channels != channels {
+                anyhow::bail!(
+                    "default audio track already configured for {} ch, requested {} ch",
+                    pipeline.channels,
+                    channels
+                );
+            }
+            return Ok(id);
+        }
+        g.default_audio_track_id = None;
+    }
+    let id = register_audio_pipeline(g, "ue-audio", sample_rate, channels, 1_000)?;
+    g.default_audio_track_id = Some(id);
+    Ok(id)
+}
+
+fn create_audio_pipeline(
+    mut g: &mut ClientState,
+    label: &str,
+    sample_rate: u32,
+    channels: u32,
+    buffer_ms: u32,
+) -> Result<AudioPipeline> {
+    if sample_rate == 0 || channels == 0 {
+        anyhow::bail!("invalid audio parameters");
+    }
+    let buffer_ms = buffer_ms.clamp(100, 5_000);
+    let samples_per_10ms = (sample_rate / 100).max(1);
+    let src = NativeAudioSource::new(
+        AudioSourceOptions::default(),
+        sample_rate,
+        channels,
+        samples_per_10ms,
+    );
+    let local = LocalAudioTrack::create_audio_track(label, RtcAudioSource::Native(src.clone()));
+    let room = g
+        .room
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("not connected"))?;
+    let rt = g.rt.clone();
+    let publish_res = rt.block_on(async {
+        room.local_participant()
+            .publish_track(LocalTrack::Audio(local.clone()), TrackPublishOptions::default())
+            .await
+    });
+    match publish_res {
+        Ok(_) => lk_log!(
+            g,
+            LkLogLevel::Info,
+            LkLogCategory::Audio,
+            "Published audio track '{}' (sr={} ch={} buffer={}ms)",
+            label,
+            sample_rate,
+            channels,
+            buffer_ms
+        ),
+        Err(e) => {
+            lk_log!(
+                g,
+                LkLogLevel::Error,
+                LkLogCategory::Audio,
+                "Failed to publish audio track '{}': {}",
+                label,
+                e
+            );
+            return Err(e.into());
+        }
+    }
+
+    let safe_channels = channels.max(1);
+    let safe_channels_for_worker = safe_channels;
+    let capacity_samples = ((sample_rate as usize * channels as usize) * buffer_ms as usize / 1_000)
+        .max(samples_per_10ms as usize * channels as usize)
+        .max(1);
+    let (prod, mut cons) = RingBuffer::<i16>::new(capacity_samples);
+    let frame_samples = ((sample_rate as usize / 100) * channels as usize).max(1);
+    let underruns = Arc::new(AtomicI32::new(0));
+    let overruns = Arc::new(AtomicI32::new(0));
+    let underruns_clone = underruns.clone();
+    let src_clone = src.clone();
+    let consumer_rt = g.rt.clone();
+    let mic_muted = g.mic_muted.clone();
+    let shm = Arc::new(ArcSwapOption::<ShmAudioRing>::empty());
+    let shm_worker = shm.clone();
+
+    let worker = consumer_rt.spawn(async move {
+        let mut tick = interval(Duration::from_millis(10));
+        let mut buf: Vec<i16> = vec![0; frame_samples];
+        loop {
+            tick.tick().await;
+
+            if mic_muted.load(Ordering::Relaxed) {
+                // Leave the ring untouched so un-muting resumes without a gap.
+                continue;
+            }
+
+            let filled = if let Some(shm_ring) = shm_worker.load_full() {
+                shm_ring.pop_into(&mut buf)
+            } else {
+                let mut got = 0usize;
+                while got < buf.len() {
+                    match cons.pop() {
+                        Ok(s) => {
+                            buf[got] = s;
+                            got += 1;
+                        }
+                        Err(_) => break,
+                    }
+                }
+                if got < buf.len() {
+                    for x in &mut buf[got..] {
+                        *x = 0;
+                    }
+                }
+                got == buf.len()
+            };
+            if !filled {
+                underruns_clone.fetch_add(1, Ordering::Relaxed);
+            }
+
+            let samples_per_channel = (buf.len() as u32) / safe_channels_for_worker;
+            let frame = AudioFrame {
+                data: Cow::Borrowed(&buf[..]),
+                sample_rate,
+                num_channels: channels,
+                samples_per_channel,
+            };
+            let _ = src_clone.capture_frame(&frame).await;
+        }
+    });
+
+    let ring = AudioRing {
+        prod,
+        capacity_frames: (capacity_samples / (safe_channels as usize)).max(1),
+        underruns,
+        overruns,
+    };
+
+    Ok(AudioPipeline {
+        label: label.to_string(),
+        sample_rate,
+        channels,
+        ring,
+        local_track: local,
+        src,
+        worker,
+        shm,
+        resampler: Resampler::new(channels as usize, sample_rate, sample_rate),
+    })
+}
+
+/// Publishes interleaved i16 PCM to the client's default audio track. Under
+/// ordinary (uncontended) conditions this returns the real connection/
+/// pipeline-init/ring-push error to the caller. If the mutex happens to be
+/// held by another call, the frame is instead handed to the background
+/// command actor and this returns `ok()` even if the actor later fails to
+/// push it - that failure is not returned to this call, only logged at
+/// `LkLogLevel::Error` through the same log ring `lk_log!` feeds elsewhere,
+/// so it is visible to a host that's draining logs, just not to this
+/// particular call's return value.
+#[no_mangle]
+pub extern "C" fn lk_publish_audio_pcm_i16(
+    client: *mut LkClientHandle,
+    pcm: *const i16,
+    frames_per_channel: usize,
+    channels: c_int,
+    sample_rate: c_int,
+) -> LkResult {
+    if client.is_null() {
+        return err(1, "client null");
+    }
+    if pcm.is_null() {
+        return err(4, "pcm null");
+    }
+    if channels <= 0 || sample_rate <= 0 {
+        return err(5, "bad params");
+    }
+
+    let c = unsafe { &*(client as *const Client) };
+    let channels = channels as u32;
+    let sample_rate = sample_rate as u32;
+    let total = frames_per_channel * channels as usize;
+    let slice = unsafe { std::slice::from_raw_parts(pcm, total) };
+
+    // Fast path: if ClientState's mutex is free, validate and push
+    // synchronously so callers still see real errors (not connected,
+    // pipeline init failure, ring push failure) instead of a blind ok().
+    // Only fall back to the async actor when the lock is contended, so the
+    // hot path from the host's audio thread still doesn't block on it.
+    if let Ok(mut g) = c.0.try_lock() {
+        if g.room.is_none() {
+            return err(6, "not connected");
+        }
+        let track_id = match ensure_default_audio_track(&mut g, sample_rate, channels) {
+            Ok(id) => id,
+            Err(e) => {
+                let msg = format!("audio pipeline init failed: {}", e);
+                lk_log!(g, LkLogLevel::Error, LkLogCategory::Audio, "{}", msg);
+                return err(7, &msg);
+            }
+        };
+        return match g.audio_tracks.get_mut(&track_id) {
+            Some(pipeline) => match pipeline.push(slice, sample_rate) {
+                Ok(()) => ok(),
+                Err(e) => {
+                    let msg = format!("audio ring push failed: {}", e);
+                    lk_log!(g, LkLogLevel::Error, LkLogCategory::Audio, "{}", msg);
+                    err(8, &msg)
+                }
+            },
+            None => err(6, "audio track not found"),
+        };
+    }
+
+    // Mutex contended: hand the frame to the command actor and return
+    // immediately rather than block the caller's audio thread. This call
+    // still returns ok() even if the actor later hits a not-connected,
+    // pipeline-init, or ring-push error - those aren't returned to this
+    // call, only logged at Error level through the log ring (see the actor
+    // in spawn_command_actor), so they're visible to a host draining logs
+    // but not to this particular return value.
+    if c.2.send(Command::PushAudioPcm { pcm: slice.to_vec(), channels, sample_rate }).is_err() {
+        return err(6, "client shutting down");
+    }
+
+    ok()
+}
+
+fn f32_to_i16(s: f32) -> i16 {
+    (s.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16
+}
+
+/// Same as `lk_publish_audio_pcm_i16` but accepts interleaved f32 in
+/// `[-1.0, 1.0]` (UE's native capture format). `sample_rate` need not match
+/// the default track's own rate - the pipeline resamples on push.
+#[no_mangle]
+pub extern "C" fn lk_publish_audio_pcm_f32(
+    client: *mut LkClientHandle,
+    pcm: *const c_float,
+    frames_per_channel: usize,
+    channels: c_int,
+    sample_rate: c_int,
+) -> LkResult {
+    if client.is_null() {
+        return err(1, "client null");
+    }
+    if pcm.is_null() {
+        return err(4, "pcm null");
+    }
+    if channels <= 0 || sample_rate <= 0 {
+        return err(5, "bad params");
+    }
+
+    let c = unsafe { &*(client as *const Client) };
+    let channels = channels as u32;
+    let sample_rate = sample_rate as u32;
+    let total = frames_per_channel * channels as usize;
+    let pcm_f32 = unsafe { std::slice::from_raw_parts(pcm, total) };
+    let pcm: Vec<i16> = pcm_f32.iter().map(|&s| f32_to_i16(s)).collect();
+
+    // Fast path: if ClientState's mutex is free, validate and push
+    // synchronously so callers still see real errors (not connected,
+    // pipeline init failure, ring push failure) instead of a blind ok().
+    // Only fall back to the async actor when the lock is contended, so the
+    // hot path from the host's audio thread still doesn't block on it.
+    if let Ok(mut g) = c.0.try_lock() {
+        if g.room.is_none() {
+            return err(6, "not connected");
+        }
+        let track_id = match ensure_default_audio_track(&mut g, sample_rate, channels) {
+            Ok(id) => id,
+            Err(e) => {
+                let msg = format!("audio pipeline init failed: {}", e);
+                lk_log!(g, LkLogLevel::Error, LkLogCategory::Audio, "{}", msg);
+                return err(7, &msg);
+            }
+        };
+        return match g.audio_tracks.get_mut(&track_id) {
+            Some(pipeline) => match pipeline.push(&pcm, sample_rate) {
+                Ok(()) => ok(),
+                Err(e) => {
+                    let msg = format!("audio ring push failed: {}", e);
+                    lk_log!(g, LkLogLevel::Error, LkLogCategory::Audio, "{}", msg);
+                    err(8, &msg)
+                }
+            },
+            None => err(6, "audio track not found"),
+        };
+    }
+
+    // Mutex contended: hand the frame to the command actor and return
+    // immediately rather than block the caller's audio thread. This call
+    // still returns ok() even if the actor later hits a not-connected,
+    // pipeline-init, or ring-push error - those aren't returned to this
+    // call, only logged at Error level through the log ring (see the actor
+    // in spawn_command_actor), so they're visible to a host draining logs
+    // but not to this particular return value.
+    if c.2.send(Command::PushAudioPcm { pcm, channels, sample_rate }).is_err() {
+        return err(6, "client shutting down");
+    }
 
     ok()
 }
+
 #[no_mangle]
-pub extern "C" fn lk_disconnect(client: *mut LkClientHandle) -> LkResult {
+pub extern "C" fn lk_audio_track_create(
+    client: *mut LkClientHandle,
+    config: *const LkAudioTrackConfig,
+    out_track: *mut *mut LkAudioTrackHandle,
+) -> LkResult {
     if client.is_null() {
         return err(1, "client null");
     }
+    if config.is_null() {
+        return err(5, "config null");
+    }
+    if out_track.is_null() {
+        return err(5, "out_track null");
+    }
+
+    let cfg = unsafe { &*config };
+    if cfg.sample_rate <= 0 || cfg.channels <= 0 {
+        return err(5, "invalid audio track parameters");
+    }
+
+    let label = if cfg.track_name.is_null() {
+        "ue-audio-track"
+    } else {
+        match unsafe { cstr(cfg.track_name) } {
+            Ok(s) => s,
+            Err(_) => "ue-audio-track",
+        }
+    };
+    let buffer_ms = if cfg.buffer_ms <= 0 { 1_000 } else { cfg.buffer_ms };
+
     let c = unsafe { &*(client as *const Client) };
     let mut g = c.0.lock().unwrap();
+    let track_id = match register_audio_pipeline(
+        &mut g,
+        label,
+        cfg.sample_rate as u32,
+        cfg.channels as u32,
+        buffer_ms as u32,
+    ) {
+        Ok(id) => id,
+        Err(e) => {
+            let msg = format!("audio track create failed: {}", e);
+            return err(7, &msg);
+        }
+    };
 
-    if let Some(room) = g.room.take() {
-        let rt = g.rt.clone();
-        let _ = rt.block_on(async move {
-            let _ = room.close().await; // graceful shutdown
-        });
+    let handle = Box::new(LkAudioTrackHandle(AudioTrackHandleRef {
+        client: c.0.clone(),
+        track_id,
+    }));
+    unsafe {
+        *out_track = Box::into_raw(handle);
     }
-    lk_log!(g, LkLogLevel::Info, "Disconnected");
-    g.audio_tracks.clear();
-    g.default_audio_track_id = None;
     ok()
 }
 
 #[no_mangle]
-pub extern "C" fn lk_client_is_ready(client: *mut LkClientHandle) -> c_int {
+pub extern "C" fn lk_audio_track_destroy(track: *mut LkAudioTrackHandle) -> LkResult {
+    if track.is_null() {
+        return err(1, "track null");
+    }
+    unsafe {
+        let handle = Box::from_raw(track);
+        let client = handle.0.client.clone();
+        let track_id = handle.0.track_id;
+        drop(handle);
+
+        let mut g = client.lock().unwrap();
+        let _ = g.audio_tracks.remove(&track_id);
+        if g.default_audio_track_id == Some(track_id) {
+            g.default_audio_track_id = None;
+        }
+    }
+    ok()
+}
+
+#[no_mangle]
+pub extern "C" fn lk_audio_track_publish_pcm_i16(
+    track: *mut LkAudioTrackHandle,
+    pcm: *const i16,
+    frames_per_channel: usize,
+) -> LkResult {
+    if track.is_null() {
+        return err(1, "track null");
+    }
+    if pcm.is_null() {
+        return err(4, "pcm null");
+    }
+    let handle = unsafe { &*(track as *mut LkAudioTrackHandle) };
+    let client = handle.0.client.clone();
+    let mut g = client.lock().unwrap();
+    let pipeline = match g.audio_tracks.get_mut(&handle.0.track_id) {
+        Some(p) => p,
+        None => return err(6, "audio track not found"),
+    };
+    let total = frames_per_channel * pipeline.channels as usize;
+    let slice = unsafe { std::slice::from_raw_parts(pcm, total) };
+    let in_rate = pipeline.sample_rate;
+    if let Err(e) = pipeline.push(slice, in_rate) {
+        let msg = format!("audio ring push failed: {}", e);
+        return err(8, &msg);
+    }
+    ok()
+}
+
+/// Same as `lk_audio_track_publish_pcm_i16` but accepts interleaved f32 in
+/// `[-1.0, 1.0]` (UE's native capture format), converting to i16 before the
+/// existing ring-push path.
+#[no_mangle]
+pub extern "C" fn lk_audio_track_publish_pcm_f32(
+    track: *mut LkAudioTrackHandle,
+    pcm: *const c_float,
+    frames_per_channel: usize,
+    sample_rate: c_int,
+) -> LkResult {
+    if track.is_null() {
+        return err(1, "track null");
+    }
+    if pcm.is_null() {
+        return err(4, "pcm null");
+    }
+    if sample_rate <= 0 {
+        return err(5, "bad params");
+    }
+    let handle = unsafe { &*(track as *mut LkAudioTrackHandle) };
+    let client = handle.0.client.clone();
+    let mut g = client.lock().unwrap();
+    let pipeline = match g.audio_tracks.get_mut(&handle.0.track_id) {
+        Some(p) => p,
+        None => return err(6, "audio track not found"),
+    };
+    let total = frames_per_channel * pipeline.channels as usize;
+    let slice = unsafe { std::slice::from_raw_parts(pcm, total) };
+    let converted: Vec<i16> = slice.iter().map(|&s| f32_to_i16(s)).collect();
+    if let Err(e) = pipeline.push(&converted, sample_rate as u32) {
+        let msg = format!("audio ring push failed: {}", e);
+        return err(8, &msg);
+    }
+    ok()
+}
+
+/// Hands the host a descriptor for this track's ring so it can write
+/// samples directly and skip `lk_audio_track_publish_pcm_i16` entirely.
+/// Safe to call more than once; each call replaces the previous ring (the
+/// old descriptor becomes stale - a host that remaps should not keep using
+/// a pointer from an earlier `lk_audio_track_map_shm` call).
+///
+/// # Safety
+/// The caller must ensure `out_desc` points to valid writable memory, and
+/// must not write past `capacity_samples` at `base_ptr` nor touch it after
+/// calling `lk_audio_track_unmap_shm` or `lk_audio_track_destroy`.
+#[no_mangle]
+pub unsafe extern "C" fn lk_audio_track_map_shm(
+    track: *mut LkAudioTrackHandle,
+    out_desc: *mut LkAudioShmDescriptor,
+) -> LkResult {
+    if track.is_null() {
+        return err(1, "track null");
+    }
+    if out_desc.is_null() {
+        return err(5, "out_desc null");
+    }
+    let handle = &*(track as *mut LkAudioTrackHandle);
+    let client = handle.0.client.clone();
+    let mut g = client.lock().unwrap();
+    let pipeline = match g.audio_tracks.get_mut(&handle.0.track_id) {
+        Some(p) => p,
+        None => return err(6, "audio track not found"),
+    };
+
+    let capacity_samples = pipeline.ring.capacity_frames * pipeline.channels as usize;
+    let ring = Arc::new(ShmAudioRing::new(capacity_samples));
+    let desc = LkAudioShmDescriptor {
+        base_ptr: ring.base_ptr(),
+        capacity_samples: ring.capacity,
+        head_atomic_ptr: &ring.head as *const AtomicI64 as *mut i64,
+        tail_atomic_ptr: &ring.tail as *const AtomicI64 as *mut i64,
+    };
+    pipeline.shm.store(Some(ring));
+    *out_desc = desc;
+    ok()
+}
+
+/// Falls back to `lk_audio_track_publish_pcm_i16` pushes; any pointers from
+/// a prior `lk_audio_track_map_shm` call must not be used afterward.
+#[no_mangle]
+pub extern "C" fn lk_audio_track_unmap_shm(track: *mut LkAudioTrackHandle) -> LkResult {
+    if track.is_null() {
+        return err(1, "track null");
+    }
+    let handle = unsafe { &*(track as *mut LkAudioTrackHandle) };
+    let client = handle.0.client.clone();
+    let mut g = client.lock().unwrap();
+    match g.audio_tracks.get_mut(&handle.0.track_id) {
+        Some(pipeline) => {
+            pipeline.shm.store(None);
+            ok()
+        }
+        None => err(6, "audio track not found"),
+    }
+}
+
+// --------- Standalone zero-copy audio ring ---------
+//
+// Unlike `ShmAudioRing` (which is always attached to one AudioPipeline and
+// consumed by its own 10ms worker), this ring isn't tied to any track: it's a
+// plain SPSC byte pipe a host can create, write into and read out of across
+// the FFI boundary with no per-frame allocation. Capacity is rounded up to a
+// power of two so indexing is `& (capacity - 1)` instead of `% capacity`.
+
+struct PowerOfTwoAudioRing {
+    buf: Vec<i16>,
+    capacity_frames: usize,
+    channels: usize,
+    sample_rate: i32,
+    write_cursor: AtomicI64,
+    read_cursor: AtomicI64,
+    underruns: AtomicI32,
+    overruns: AtomicI32,
+}
+
+impl PowerOfTwoAudioRing {
+    fn new(capacity_frames: usize, sample_rate: i32, channels: usize) -> Self {
+        let capacity_frames = capacity_frames.max(1).next_power_of_two();
+        Self {
+            buf: vec![0i16; capacity_frames * channels.max(1)],
+            capacity_frames,
+            channels: channels.max(1),
+            sample_rate,
+            write_cursor: AtomicI64::new(0),
+            read_cursor: AtomicI64::new(0),
+            underruns: AtomicI32::new(0),
+            overruns: AtomicI32::new(0),
+        }
+    }
+
+    /// Frames currently buffered (written but not yet read), the same
+    /// quantity `LkAudioStats.ring_queued_frames` reports for a track's ring.
+    fn queued_frames(&self) -> usize {
+        let write = self.write_cursor.load(Ordering::Acquire);
+        let read = self.read_cursor.load(Ordering::Acquire);
+        (write - read).max(0) as usize
+    }
+
+    /// Writes as many whole interleaved frames from `data` as fit,
+    /// returning how many frames were actually accepted; the rest are
+    /// dropped and counted as an overrun, matching the push-side behavior of
+    /// `AudioPipeline::push`'s ring.
+    fn write(&self, data: &[i16]) -> usize {
+        let write = self.write_cursor.load(Ordering::Relaxed);
+        let read = self.read_cursor.load(Ordering::Acquire);
+        let free_frames = self.capacity_frames - (write - read) as usize;
+        let in_frames = data.len() / self.channels;
+        let to_write = in_frames.min(free_frames);
+        let mask = self.capacity_frames - 1;
+        let base = self.buf.as_ptr() as *mut i16;
+        for f in 0..to_write {
+            let idx = (write as usize + f) & mask;
+            for ch in 0..self.channels {
+                unsafe {
+                    std::ptr::write_volatile(base.add(idx * self.channels + ch), data[f * self.channels + ch]);
+                }
+            }
+        }
+        self.write_cursor.store(write + to_write as i64, Ordering::Release);
+        if to_write < in_frames {
+            self.overruns.fetch_add(1, Ordering::Relaxed);
+        }
+        to_write
+    }
+
+    /// Reads up to `out.len() / channels` interleaved frames into `out`,
+    /// returning how many frames were actually available; the remainder of
+    /// `out` is left untouched (the caller knows how much was filled from
+    /// the return value, so zero-padding the rest would just be wasted work).
+    fn read(&self, out: &mut [i16]) -> usize {
+        let write = self.write_cursor.load(Ordering::Acquire);
+        let read = self.read_cursor.load(Ordering::Relaxed);
+        let available_frames = (write - read).max(0) as usize;
+        let out_frames = out.len() / self.channels;
+        let to_read = available_frames.min(out_frames);
+        let mask = self.capacity_frames - 1;
+        let base = self.buf.as_ptr() as *mut i16;
+        for f in 0..to_read {
+            let idx = (read as usize + f) & mask;
+            for ch in 0..self.channels {
+                out[f * self.channels + ch] = unsafe { std::ptr::read_volatile(base.add(idx * self.channels + ch)) };
+            }
+        }
+        self.read_cursor.store(read + to_read as i64, Ordering::Release);
+        if to_read < out_frames {
+            self.underruns.fetch_add(1, Ordering::Relaxed);
+        }
+        to_read
+    }
+}
+
+#[cfg(test)]
+mod power_of_two_audio_ring_tests {
+    use super::PowerOfTwoAudioRing;
+    use std::sync::atomic::Ordering;
+
+    #[test]
+    fn capacity_rounds_up_to_power_of_two() {
+        let ring = PowerOfTwoAudioRing::new(100, 48_000, 2);
+        assert_eq!(ring.capacity_frames, 128);
+    }
+
+    #[test]
+    fn write_then_read_round_trips_samples() {
+        let ring = PowerOfTwoAudioRing::new(4, 48_000, 2);
+        let written = ring.write(&[1, 2, 3, 4]);
+        assert_eq!(written, 2);
+        let mut out = vec![0i16; 4];
+        let read = ring.read(&mut out);
+        assert_eq!(read, 2);
+        assert_eq!(out, vec![1, 2, 3, 4]);
+        assert_eq!(ring.underruns.load(Ordering::Relaxed), 0);
+        assert_eq!(ring.overruns.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn write_past_capacity_drops_excess_and_counts_overrun() {
+        let ring = PowerOfTwoAudioRing::new(2, 48_000, 1);
+        let written = ring.write(&[1, 2, 3, 4]);
+        assert_eq!(written, 2);
+        assert_eq!(ring.overruns.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn read_past_available_counts_underrun() {
+        let ring = PowerOfTwoAudioRing::new(4, 48_000, 1);
+        ring.write(&[1]);
+        let mut out = vec![0i16; 4];
+        let read = ring.read(&mut out);
+        assert_eq!(read, 1);
+        assert_eq!(ring.underruns.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn queued_frames_tracks_unread_backlog() {
+        let ring = PowerOfTwoAudioRing::new(8, 48_000, 1);
+        ring.write(&[1, 2, 3]);
+        assert_eq!(ring.queued_frames(), 3);
+        let mut out = vec![0i16; 2];
+        ring.read(&mut out);
+        assert_eq!(ring.queued_frames(), 1);
+    }
+
+    #[test]
+    fn indices_wrap_around_capacity() {
+        let ring = PowerOfTwoAudioRing::new(2, 48_000, 1);
+        let mut out = vec![0i16; 1];
+        for i in 0..10i16 {
+            assert_eq!(ring.write(&[i]), 1);
+            assert_eq!(ring.read(&mut out), 1);
+            assert_eq!(out[0], i);
+        }
+    }
+}
+
+#[repr(C)]
+pub struct LkAudioRingHandle(Arc<PowerOfTwoAudioRing>);
+
+/// Creates a standalone lock-free SPSC ring for interleaved i16 audio,
+/// independent of any published/subscribed track, so a host can move whole
+/// blocks of samples across the FFI boundary without the per-call allocation
+/// `lk_audio_track_publish_pcm_i16`/the data callback path both pay.
+/// `capacity_frames` is rounded up to the next power of two.
+///
+/// # Safety
+/// `out_handle` must point to valid writable memory; the returned handle
+/// must eventually be released with `lk_audio_ring_destroy`.
+#[no_mangle]
+pub unsafe extern "C" fn lk_audio_ring_create(
+    client: *mut LkClientHandle,
+    capacity_frames: c_int,
+    sample_rate: c_int,
+    channels: c_int,
+    out_handle: *mut *mut LkAudioRingHandle,
+) -> LkResult {
     if client.is_null() {
+        return err(1, "client null");
+    }
+    if out_handle.is_null() {
+        return err(4, "out_handle null");
+    }
+    if capacity_frames <= 0 || sample_rate <= 0 || channels <= 0 {
+        return err(5, "invalid audio ring parameters");
+    }
+    let ring = Arc::new(PowerOfTwoAudioRing::new(capacity_frames as usize, sample_rate, channels as usize));
+    *out_handle = Box::into_raw(Box::new(LkAudioRingHandle(ring)));
+    ok()
+}
+
+#[no_mangle]
+pub extern "C" fn lk_audio_ring_destroy(handle: *mut LkAudioRingHandle) -> LkResult {
+    if handle.is_null() {
+        return err(1, "handle null");
+    }
+    unsafe {
+        drop(Box::from_raw(handle));
+    }
+    ok()
+}
+
+/// Writes `frames` interleaved frames from `data`, returning how many were
+/// actually accepted (the rest are dropped and counted as an overrun).
+///
+/// # Safety
+/// `data` must point to at least `frames * channels` readable `i16`s.
+#[no_mangle]
+pub unsafe extern "C" fn lk_audio_ring_write(
+    handle: *mut LkAudioRingHandle,
+    data: *const i16,
+    frames: usize,
+) -> usize {
+    if handle.is_null() || data.is_null() || frames == 0 {
         return 0;
     }
-    let c = unsafe { &*(client as *const Client) };
-    let g = c.0.lock().unwrap();
-    if g.room.is_some() { 1 } else { 0 }
+    let h = &*handle;
+    let slice = std::slice::from_raw_parts(data, frames * h.0.channels);
+    h.0.write(slice)
 }
 
-fn next_audio_track_id(g: &mut ClientState) -> u64 {
-    let id = g.next_audio_track_id;
-    g.next_audio_track_id = g.next_audio_track_id.wrapping_add(1);
-    if g.next_audio_track_id == 0 {
-        g.next_audio_track_id = 1;
+/// Reads up to `frames` interleaved frames into `out`, returning how many
+/// were actually available (the rest are left untouched and counted as an
+/// underrun).
+///
+/// # Safety
+/// `out` must point to at least `frames * channels` writable `i16`s.
+#[no_mangle]
+pub unsafe extern "C" fn lk_audio_ring_read(
+    handle: *mut LkAudioRingHandle,
+    out: *mut i16,
+    frames: usize,
+) -> usize {
+    if handle.is_null() || out.is_null() || frames == 0 {
+        return 0;
+    }
+    let h = &*handle;
+    let slice = std::slice::from_raw_parts_mut(out, frames * h.0.channels);
+    h.0.read(slice)
+}
+
+/// Reuses `LkAudioStats` (the same struct `lk_get_audio_stats` fills in for
+/// a published track's ring) rather than a ring-specific stats type, so a
+/// host reads both rings' `underruns`/`overruns` the same way.
+///
+/// # Safety
+/// `out_stats` must point to valid writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn lk_audio_ring_get_stats(
+    handle: *mut LkAudioRingHandle,
+    out_stats: *mut LkAudioStats,
+) -> LkResult {
+    if handle.is_null() {
+        return err(1, "handle null");
+    }
+    if out_stats.is_null() {
+        return err(4, "out_stats null");
+    }
+    let h = &*handle;
+    *out_stats = LkAudioStats {
+        sample_rate: h.0.sample_rate,
+        channels: h.0.channels.min(c_int::MAX as usize) as c_int,
+        ring_capacity_frames: h.0.capacity_frames.min(c_int::MAX as usize) as c_int,
+        ring_queued_frames: h.0.queued_frames().min(c_int::MAX as usize) as c_int,
+        underruns: h.0.underruns.load(Ordering::Relaxed),
+        overruns: h.0.overruns.load(Ordering::Relaxed),
+    };
+    ok()
+}
+
+fn next_video_track_id(g: &mut ClientState) -> u64 {
+    let id = g.next_video_track_id;
+    g.next_video_track_id = g.next_video_track_id.wrapping_add(1);
+    if g.next_video_track_id == 0 {
+        g.next_video_track_id = 1;
     }
     id
 }
 
-fn register_audio_pipeline(
-    g: &mut ClientState,
-    label: &str,
-    sample_rate: u32,
-    channels: u32,
-    buffer_ms: u32,
-) -> Result<u64> {
-    let id = next_audio_track_id(g);
-    let pipeline = create_audio_pipeline(g, label, sample_rate, channels, buffer_ms)?;
-    g.audio_tracks.insert(id, pipeline);
-    Ok(id)
+// Minimum byte length a pushed buffer must have for `width`x`height` in
+// `format`, used to reject undersized buffers before they reach
+// `convert_to_i420`'s unchecked slice arithmetic.
+fn expected_video_frame_len(width: u32, height: u32, format: LkVideoFormat) -> usize {
+    let w = width as usize;
+    let h = height as usize;
+    match format {
+        LkVideoFormat::I420 => {
+            let chroma_w = (w + 1) / 2;
+            let chroma_h = (h + 1) / 2;
+            w * h + 2 * chroma_w * chroma_h
+        }
+        LkVideoFormat::Nv12 => {
+            let chroma_w = (w + 1) / 2;
+            let chroma_h = (h + 1) / 2;
+            w * h + 2 * chroma_w * chroma_h
+        }
+        LkVideoFormat::Rgba | LkVideoFormat::Bgra => w * h * 4,
+    }
 }
 
-fn ensure_default_audio_track(g: &mut ClientState, sample_rate: u32, channels: u32) -> Result<u64> {
-    if let Some(id) = g.default_audio_track_id {
-        if let Some(pipeline) = g.audio_tracks.get(&id) {
-            if pipeline.sample_rate != sample_rate || pipeline.channels != channels {
-                anyhow::bail!(
-                    "default audio track already configured for {} Hz ({} ch), requested {} Hz ({} ch)",
-                    pipeline.sample_rate,
-                    pipeline.channels,
-                    sample_rate,
-                    channels
-                );
-            }
-            return Ok(id);
+// Converts a pushed buffer in its source pixel format into an I420Buffer,
+// which is the only format NativeVideoSource::capture_frame accepts.
+fn convert_to_i420(frame: &PendingVideoFrame) -> I420Buffer {
+    let width = frame.width as i32;
+    let height = frame.height as i32;
+    let mut i420 = I420Buffer::new(frame.width, frame.height);
+    let stride_y = i420.stride_y();
+    let stride_u = i420.stride_u();
+    let stride_v = i420.stride_v();
+    let (data_y, data_u, data_v) = i420.data_mut();
+    match frame.format {
+        LkVideoFormat::I420 => {
+            let y_len = data_y.len().min(frame.buf.len());
+            data_y[..y_len].copy_from_slice(&frame.buf[..y_len]);
+            let u_off = y_len;
+            let u_len = data_u.len().min(frame.buf.len().saturating_sub(u_off));
+            data_u[..u_len].copy_from_slice(&frame.buf[u_off..u_off + u_len]);
+            let v_off = u_off + u_len;
+            let v_len = data_v.len().min(frame.buf.len().saturating_sub(v_off));
+            data_v[..v_len].copy_from_slice(&frame.buf[v_off..v_off + v_len]);
+        }
+        LkVideoFormat::Nv12 => {
+            let stride_uv = frame.width as u32;
+            let y_size = (frame.width as usize) * (frame.height as usize);
+            let y_size = y_size.min(frame.buf.len());
+            let (y_plane, uv_plane) = frame.buf.split_at(y_size);
+            yuv_helper::nv12_to_i420(
+                y_plane,
+                frame.width as u32,
+                uv_plane,
+                stride_uv,
+                data_y,
+                stride_y,
+                data_u,
+                stride_u,
+                data_v,
+                stride_v,
+                width,
+                height,
+            );
+        }
+        LkVideoFormat::Rgba => {
+            yuv_helper::abgr_to_i420(
+                &frame.buf,
+                (frame.width * 4) as u32,
+                data_y,
+                stride_y,
+                data_u,
+                stride_u,
+                data_v,
+                stride_v,
+                width,
+                height,
+            );
+        }
+        LkVideoFormat::Bgra => {
+            yuv_helper::argb_to_i420(
+                &frame.buf,
+                (frame.width * 4) as u32,
+                data_y,
+                stride_y,
+                data_u,
+                stride_u,
+                data_v,
+                stride_v,
+                width,
+                height,
+            );
         }
-        g.default_audio_track_id = None;
     }
-    let id = register_audio_pipeline(g, "ue-audio", sample_rate, channels, 1_000)?;
-    g.default_audio_track_id = Some(id);
-    Ok(id)
+    i420
 }
 
-fn create_audio_pipeline(
-    g: &mut ClientState,
+fn create_video_pipeline(
+    mut g: &mut ClientState,
     label: &str,
-    sample_rate: u32,
-    channels: u32,
-    buffer_ms: u32,
-) -> Result<AudioPipeline> {
-    if sample_rate == 0 || channels == 0 {
-        anyhow::bail!("invalid audio parameters");
+    width: u32,
+    height: u32,
+    max_queued_frames: u32,
+) -> Result<VideoPipeline> {
+    if width == 0 || height == 0 {
+        anyhow::bail!("invalid video dimensions");
     }
-    let buffer_ms = buffer_ms.clamp(100, 5_000);
-    let samples_per_10ms = (sample_rate / 100).max(1);
-    let src = NativeAudioSource::new(
-        AudioSourceOptions::default(),
-        sample_rate,
-        channels,
-        samples_per_10ms,
-    );
-    let local = LocalAudioTrack::create_audio_track(label, RtcAudioSource::Native(src.clone()));
+    let src = NativeVideoSource::new(VideoResolution { width, height });
+    let local = LocalVideoTrack::create_video_track(label, RtcVideoSource::Native(src.clone()));
     let room = g
         .room
         .as_ref()
@@ -948,254 +3958,370 @@ fn create_audio_pipeline(
     let rt = g.rt.clone();
     let publish_res = rt.block_on(async {
         room.local_participant()
-            .publish_track(LocalTrack::Audio(local.clone()), TrackPublishOptions::default())
+            .publish_track(LocalTrack::Video(local.clone()), TrackPublishOptions::default())
             .await
     });
-    match publish_res {
-        Ok(_) => lk_log!(
-            g,
-            LkLogLevel::Info,
-            "Published audio track '{}' (sr={} ch={} buffer={}ms)",
-            label,
-            sample_rate,
-            channels,
-            buffer_ms
-        ),
-        Err(e) => {
-            lk_log!(
-                g,
-                LkLogLevel::Error,
-                "Failed to publish audio track '{}': {}",
-                label,
-                e
-            );
-            return Err(e.into());
-        }
+    if let Err(e) = publish_res {
+        lk_log!(g, LkLogLevel::Error, LkLogCategory::Rtc, "Failed to publish video track '{}': {}", label, e);
+        return Err(e.into());
     }
+    lk_log!(g, LkLogLevel::Info, LkLogCategory::Rtc, "Published video track '{}' ({}x{})", label, width, height);
 
-    let safe_channels = channels.max(1);
-    let safe_channels_for_worker = safe_channels;
-    let capacity_samples = ((sample_rate as usize * channels as usize) * buffer_ms as usize / 1_000)
-        .max(samples_per_10ms as usize * channels as usize)
-        .max(1);
-    let (prod, mut cons) = RingBuffer::<i16>::new(capacity_samples);
-    let frame_samples = ((sample_rate as usize / 100) * channels as usize).max(1);
-    let underruns = Arc::new(AtomicI32::new(0));
-    let overruns = Arc::new(AtomicI32::new(0));
-    let underruns_clone = underruns.clone();
+    let queue = Arc::new(Mutex::new(VideoFrameQueue {
+        frames: VecDeque::with_capacity(max_queued_frames as usize),
+        capacity: max_queued_frames.max(1) as usize,
+    }));
+    let notify = Arc::new(Notify::new());
+    let dropped_frames = Arc::new(AtomicI32::new(0));
+    let worker_queue = queue.clone();
+    let worker_notify = notify.clone();
     let src_clone = src.clone();
-    let consumer_rt = g.rt.clone();
-
-    let worker = consumer_rt.spawn(async move {
-        let mut tick = interval(Duration::from_millis(10));
-        let mut buf: Vec<i16> = vec![0; frame_samples];
+    let worker = g.rt.spawn(async move {
         loop {
-            tick.tick().await;
-
-            let mut got = 0usize;
-            while got < buf.len() {
-                match cons.pop() {
-                    Ok(s) => {
-                        buf[got] = s;
-                        got += 1;
-                    }
-                    Err(_) => break,
-                }
-            }
-            if got < buf.len() {
-                underruns_clone.fetch_add(1, Ordering::Relaxed);
-                for x in &mut buf[got..] {
-                    *x = 0;
-                }
+            worker_notify.notified().await;
+            loop {
+                let next = { worker_queue.lock().unwrap().frames.pop_front() };
+                let Some(pending) = next else { break; };
+                let timestamp_us = pending.timestamp_us;
+                let i420 = convert_to_i420(&pending);
+                let rotation = VideoRotation::VideoRotation0;
+                let rtc_frame = VideoFrame {
+                    rotation,
+                    timestamp_us,
+                    buffer: i420,
+                };
+                src_clone.capture_frame(&rtc_frame);
             }
-
-            let samples_per_channel = (buf.len() as u32) / safe_channels_for_worker;
-            let frame = AudioFrame {
-                data: Cow::Borrowed(&buf[..]),
-                sample_rate,
-                num_channels: channels,
-                samples_per_channel,
-            };
-            let _ = src_clone.capture_frame(&frame).await;
         }
     });
 
-    let ring = AudioRing {
-        prod,
-        capacity_frames: (capacity_samples / (safe_channels as usize)).max(1),
-        underruns,
-        overruns,
-    };
-
-    Ok(AudioPipeline {
+    Ok(VideoPipeline {
         label: label.to_string(),
-        sample_rate,
-        channels,
-        ring,
+        width,
+        height,
+        queue,
+        notify,
         local_track: local,
         src,
         worker,
+        dropped_frames,
     })
 }
 
 #[no_mangle]
-pub extern "C" fn lk_publish_audio_pcm_i16(
+pub extern "C" fn lk_publish_video_track(
     client: *mut LkClientHandle,
-    pcm: *const i16,
-    frames_per_channel: usize,
-    channels: c_int,
-    sample_rate: c_int,
+    config: *const LkVideoTrackConfig,
+    out_track: *mut *mut LkVideoTrackHandle,
 ) -> LkResult {
-    if client.is_null() {
-        return err(1, "client null");
-    }
-    if pcm.is_null() {
-        return err(4, "pcm null");
-    }
-    if channels <= 0 || sample_rate <= 0 {
-        return err(5, "bad params");
+    if client.is_null() { return err(1, "client null"); }
+    if config.is_null() { return err(5, "config null"); }
+    if out_track.is_null() { return err(5, "out_track null"); }
+
+    let cfg = unsafe { &*config };
+    if cfg.width <= 0 || cfg.height <= 0 {
+        return err(5, "invalid video track parameters");
     }
+    let label = if cfg.track_name.is_null() {
+        "ue-video-track"
+    } else {
+        match unsafe { cstr(cfg.track_name) } {
+            Ok(s) => s,
+            Err(_) => "ue-video-track",
+        }
+    };
+    let max_queued = if cfg.max_queued_frames <= 0 { 3 } else { cfg.max_queued_frames as u32 };
 
     let c = unsafe { &*(client as *const Client) };
     let mut g = c.0.lock().unwrap();
-    if g.room.is_none() {
-        return err(6, "not connected");
-    }
+    let pipeline = match create_video_pipeline(&mut g, label, cfg.width as u32, cfg.height as u32, max_queued) {
+        Ok(p) => p,
+        Err(e) => return err(7, &format!("video track create failed: {}", e)),
+    };
+    let track_id = next_video_track_id(&mut g);
+    g.video_tracks.insert(track_id, pipeline);
 
-    let channels = channels as u32;
-    let sample_rate = sample_rate as u32;
+    let handle = Box::new(LkVideoTrackHandle(VideoTrackHandleRef {
+        client: c.0.clone(),
+        track_id,
+    }));
+    unsafe { *out_track = Box::into_raw(handle); }
+    ok()
+}
 
-    let track_id = match ensure_default_audio_track(&mut g, sample_rate, channels) {
-        Ok(id) => id,
-        Err(e) => {
-            let msg = format!("audio pipeline init failed: {}", e);
-            lk_log!(g, LkLogLevel::Error, "{}", msg);
-            return err(7, &msg);
-        }
+#[no_mangle]
+pub extern "C" fn lk_video_track_destroy(track: *mut LkVideoTrackHandle) -> LkResult {
+    if track.is_null() { return err(1, "track null"); }
+    unsafe {
+        let handle = Box::from_raw(track);
+        let client = handle.0.client.clone();
+        let track_id = handle.0.track_id;
+        drop(handle);
+        let mut g = client.lock().unwrap();
+        let _ = g.video_tracks.remove(&track_id);
+    }
+    ok()
+}
+
+#[no_mangle]
+pub extern "C" fn lk_push_video_frame(
+    track: *mut LkVideoTrackHandle,
+    buf: *const u8,
+    len: usize,
+    format: LkVideoFormat,
+    timestamp_us: i64,
+) -> LkResult {
+    if track.is_null() { return err(1, "track null"); }
+    if buf.is_null() { return err(4, "buf null"); }
+    let handle = unsafe { &*(track as *mut LkVideoTrackHandle) };
+    let client = handle.0.client.clone();
+    let mut g = client.lock().unwrap();
+    let pipeline = match g.video_tracks.get_mut(&handle.0.track_id) {
+        Some(p) => p,
+        None => return err(6, "video track not found"),
     };
+    let expected = expected_video_frame_len(pipeline.width, pipeline.height, format);
+    if len < expected {
+        let msg = format!(
+            "buffer too small for {}x{} {:?}: got {} bytes, need at least {}",
+            pipeline.width, pipeline.height, format, len, expected
+        );
+        return err(5, &msg);
+    }
+    let data = unsafe { std::slice::from_raw_parts(buf, len) }.to_vec();
+    pipeline.push(PendingVideoFrame {
+        buf: data,
+        width: pipeline.width,
+        height: pipeline.height,
+        format,
+        timestamp_us,
+    });
+    ok()
+}
 
-    let total = frames_per_channel * channels as usize;
-    let slice = unsafe { std::slice::from_raw_parts(pcm, total) };
+// --------- Device capture (cpal) ---------
 
-    match g.audio_tracks.get_mut(&track_id) {
-        Some(pipeline) => {
-            if let Err(e) = pipeline.push(slice) {
-                let msg = format!("audio ring push failed: {}", e);
-                lk_log!(g, LkLogLevel::Error, "{}", msg);
-                return err(8, &msg);
-            }
-        }
-        None => {
-            let msg = "audio pipeline disappeared";
-            lk_log!(g, LkLogLevel::Error, "{}", msg);
-            return err(8, msg);
+#[repr(C)]
+pub struct LkDeviceList {
+    pub names: *mut *mut c_char,
+    pub count: usize,
+}
+
+/// # Safety
+/// The caller must ensure `out_list` points to valid writable memory, and must
+/// release the returned list with `lk_free_device_list`.
+#[no_mangle]
+pub unsafe extern "C" fn lk_enumerate_input_devices(out_list: *mut LkDeviceList) -> LkResult {
+    if out_list.is_null() {
+        return err(4, "out_list null");
+    }
+    let host = cpal::default_host();
+    let devices = match host.input_devices() {
+        Ok(d) => d,
+        Err(e) => return err(9, &format!("failed to enumerate input devices: {e}")),
+    };
+    let names: Vec<CString> = devices
+        .filter_map(|d| d.name().ok())
+        .map(|n| CString::new(n).unwrap_or_default())
+        .collect();
+    let mut ptrs: Vec<*mut c_char> = names.into_iter().map(|c| c.into_raw()).collect();
+    ptrs.shrink_to_fit();
+    let count = ptrs.len();
+    let names_ptr = ptrs.as_mut_ptr();
+    std::mem::forget(ptrs);
+    *out_list = LkDeviceList { names: names_ptr, count };
+    ok()
+}
+
+/// # Safety
+/// The caller must only pass a `LkDeviceList` previously returned by
+/// `lk_enumerate_input_devices`.
+#[no_mangle]
+pub unsafe extern "C" fn lk_free_device_list(list: LkDeviceList) {
+    if list.names.is_null() {
+        return;
+    }
+    let ptrs = Vec::from_raw_parts(list.names, list.count, list.count);
+    for p in ptrs {
+        if !p.is_null() {
+            let _ = CString::from_raw(p);
         }
     }
+}
 
+/// # Safety
+/// The caller must ensure `out_sample_rate`/`out_channels` point to valid writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn lk_query_default_input_format(
+    out_sample_rate: *mut c_int,
+    out_channels: *mut c_int,
+) -> LkResult {
+    if out_sample_rate.is_null() || out_channels.is_null() {
+        return err(4, "out params null");
+    }
+    let host = cpal::default_host();
+    let device = match host.default_input_device() {
+        Some(d) => d,
+        None => return err(9, "no default input device"),
+    };
+    let config = match device.default_input_config() {
+        Ok(c) => c,
+        Err(e) => return err(9, &format!("failed to query default input config: {e}")),
+    };
+    *out_sample_rate = config.sample_rate().0 as c_int;
+    *out_channels = config.channels() as c_int;
     ok()
 }
 
+// Converts one interleaved f32 frame buffer into i16, resampling with linear
+// interpolation when the device's native rate differs from the track's rate.
+fn resample_capture_to_i16(
+    input: &[f32],
+    channels: usize,
+    in_rate: u32,
+    out_rate: u32,
+) -> Vec<i16> {
+    if channels == 0 || input.is_empty() {
+        return Vec::new();
+    }
+    let in_frames = input.len() / channels;
+    if in_rate == out_rate || in_frames == 0 {
+        return input
+            .iter()
+            .map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16)
+            .collect();
+    }
+    let out_frames = ((in_frames as u64 * out_rate as u64) / in_rate as u64).max(1) as usize;
+    let mut out = Vec::with_capacity(out_frames * channels);
+    let step = in_frames as f64 / out_frames as f64;
+    for i in 0..out_frames {
+        let pos = i as f64 * step;
+        let idx = (pos as usize).min(in_frames - 1);
+        let next = (idx + 1).min(in_frames - 1);
+        let frac = (pos - idx as f64).clamp(0.0, 1.0) as f32;
+        for ch in 0..channels {
+            let s0 = input[idx * channels + ch];
+            let s1 = input[next * channels + ch];
+            let s = s0 + (s1 - s0) * frac;
+            out.push((s.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16);
+        }
+    }
+    out
+}
+
 #[no_mangle]
-pub extern "C" fn lk_audio_track_create(
+pub extern "C" fn lk_start_capture_from_device(
     client: *mut LkClientHandle,
-    config: *const LkAudioTrackConfig,
-    out_track: *mut *mut LkAudioTrackHandle,
+    device_name: *const c_char,
 ) -> LkResult {
     if client.is_null() {
         return err(1, "client null");
     }
-    if config.is_null() {
-        return err(5, "config null");
-    }
-    if out_track.is_null() {
-        return err(5, "out_track null");
-    }
-
-    let cfg = unsafe { &*config };
-    if cfg.sample_rate <= 0 || cfg.channels <= 0 {
-        return err(5, "invalid audio track parameters");
-    }
+    let c = unsafe { &*(client as *const Client) };
+    let client_arc = c.0.clone();
 
-    let label = if cfg.track_name.is_null() {
-        "ue-audio-track"
+    let host = cpal::default_host();
+    let device = if device_name.is_null() {
+        host.default_input_device()
     } else {
-        match unsafe { cstr(cfg.track_name) } {
-            Ok(s) => s,
-            Err(_) => "ue-audio-track",
-        }
-    };
-    let buffer_ms = if cfg.buffer_ms <= 0 { 1_000 } else { cfg.buffer_ms };
-
-    let c = unsafe { &*(client as *const Client) };
-    let mut g = c.0.lock().unwrap();
-    let track_id = match register_audio_pipeline(
-        &mut g,
-        label,
-        cfg.sample_rate as u32,
-        cfg.channels as u32,
-        buffer_ms as u32,
-    ) {
-        Ok(id) => id,
-        Err(e) => {
-            let msg = format!("audio track create failed: {}", e);
-            return err(7, &msg);
+        match unsafe { cstr(device_name) } {
+            Ok(name) if !name.is_empty() => host
+                .input_devices()
+                .ok()
+                .and_then(|mut it| it.find(|d| d.name().map(|n| n == name).unwrap_or(false))),
+            _ => host.default_input_device(),
         }
     };
+    let device = match device {
+        Some(d) => d,
+        None => return err(9, "input device not found"),
+    };
+    let config = match device.default_input_config() {
+        Ok(c) => c,
+        Err(e) => return err(9, &format!("failed to query input config: {e}")),
+    };
+    let device_rate = config.sample_rate().0;
+    let device_channels = config.channels() as u32;
 
-    let handle = Box::new(LkAudioTrackHandle(AudioTrackHandleRef {
-        client: c.0.clone(),
-        track_id,
-    }));
-    unsafe {
-        *out_track = Box::into_raw(handle);
-    }
-    ok()
-}
-
-#[no_mangle]
-pub extern "C" fn lk_audio_track_destroy(track: *mut LkAudioTrackHandle) -> LkResult {
-    if track.is_null() {
-        return err(1, "track null");
-    }
-    unsafe {
-        let handle = Box::from_raw(track);
-        let client = handle.0.client.clone();
-        let track_id = handle.0.track_id;
-        drop(handle);
+    let track_id = {
+        let mut g = client_arc.lock().unwrap();
+        match ensure_default_audio_track(&mut g, device_rate, device_channels) {
+            Ok(id) => id,
+            Err(e) => return err(7, &format!("audio pipeline init failed: {e}")),
+        }
+    };
 
-        let mut g = client.lock().unwrap();
-        let _ = g.audio_tracks.remove(&track_id);
-        if g.default_audio_track_id == Some(track_id) {
-            g.default_audio_track_id = None;
+    let stream_config: cpal::StreamConfig = config.into();
+    let callback_client = client_arc.clone();
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_thread = stop.clone();
+    let thread = std::thread::spawn(move || {
+        let err_fn = |e| eprintln!("[livekit_ffi] cpal input stream error: {e}");
+        let channels = device_channels as usize;
+        let stream = device.build_input_stream(
+            &stream_config,
+            move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                let (track_rate, track_channels) = {
+                    let Ok(g) = callback_client.lock() else { return; };
+                    let Some(p) = g.audio_tracks.get(&track_id) else { return; };
+                    (p.sample_rate, p.channels)
+                };
+                let _ = track_channels; // capture format is fixed at creation time; channel remap is out of scope
+                let converted = resample_capture_to_i16(data, channels, device_rate, track_rate);
+                if let Ok(mut g) = callback_client.lock() {
+                    if let Some(pipeline) = g.audio_tracks.get_mut(&track_id) {
+                        let _ = pipeline.push(&converted, track_rate);
+                    }
+                }
+            },
+            err_fn,
+            None,
+        );
+        match stream {
+            Ok(stream) => {
+                if let Err(e) = stream.play() {
+                    eprintln!("[livekit_ffi] failed to start cpal input stream: {e}");
+                    return;
+                }
+                // Keep the stream alive until asked to stop; dropping `stream`
+                // on the way out tears down the cpal input stream.
+                while !stop_thread.load(Ordering::Relaxed) {
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+            }
+            Err(e) => eprintln!("[livekit_ffi] failed to build cpal input stream: {e}"),
         }
+    });
+
+    if let Ok(mut g) = client_arc.lock() {
+        g.capture_threads.push(CaptureThread { stop, handle: thread });
+        lk_log!(g, LkLogLevel::Info, LkLogCategory::Audio, "Started device capture (sr={}Hz ch={})", device_rate, device_channels);
     }
+
     ok()
 }
 
+/// Stops all active device-capture threads started by
+/// `lk_start_capture_from_device`, signaling each to tear down its cpal
+/// input stream and joining it before returning.
 #[no_mangle]
-pub extern "C" fn lk_audio_track_publish_pcm_i16(
-    track: *mut LkAudioTrackHandle,
-    pcm: *const i16,
-    frames_per_channel: usize,
-) -> LkResult {
-    if track.is_null() {
-        return err(1, "track null");
-    }
-    if pcm.is_null() {
-        return err(4, "pcm null");
+pub extern "C" fn lk_stop_capture(client: *mut LkClientHandle) -> LkResult {
+    if client.is_null() {
+        return err(1, "client null");
     }
-    let handle = unsafe { &*(track as *mut LkAudioTrackHandle) };
-    let client = handle.0.client.clone();
-    let mut g = client.lock().unwrap();
-    let pipeline = match g.audio_tracks.get_mut(&handle.0.track_id) {
-        Some(p) => p,
-        None => return err(6, "audio track not found"),
+    let c = unsafe { &*(client as *const Client) };
+    let threads = {
+        let mut g = c.0.lock().unwrap();
+        g.capture_threads.drain(..).collect::<Vec<_>>()
     };
-    let total = frames_per_channel * pipeline.channels as usize;
-    let slice = unsafe { std::slice::from_raw_parts(pcm, total) };
-    if let Err(e) = pipeline.push(slice) {
-        let msg = format!("audio ring push failed: {}", e);
-        return err(8, &msg);
+    if threads.is_empty() {
+        return err(6, "no active capture");
+    }
+    for t in threads {
+        t.stop_and_join();
+    }
+    if let Ok(mut g) = c.0.lock() {
+        lk_log!(g, LkLogLevel::Info, LkLogCategory::Audio, "Stopped device capture");
     }
     ok()
 }
@@ -1228,9 +4354,9 @@ pub extern "C" fn lk_send_data_ex(
     }
     
     let c = unsafe { &*(client as *const Client) };
-    let g = c.0.lock().unwrap();
+    let mut g = c.0.lock().unwrap();
     let room = match g.room.as_ref() {
-        Some(r) => r,
+        Some(r) => r.clone(),
         None => return err(6, "not connected"),
     };
 
@@ -1240,7 +4366,7 @@ pub extern "C" fn lk_send_data_ex(
     let mut effective_rel = reliability;
     if matches!(reliability, LkReliability::Lossy) && len > LOSSY_MAX {
         effective_rel = LkReliability::Reliable;
-        lk_log!(g, LkLogLevel::Warn,
+        lk_log!(g, LkLogLevel::Warn, LkLogCategory::Data,
             "Payload size ({} bytes) exceeds lossy limit ({} bytes); switching to reliable channel",
             len, LOSSY_MAX);
     }
@@ -1273,7 +4399,8 @@ pub extern "C" fn lk_send_data_ex(
     let stats = g.data_stats.clone();
     let effective_rel_copy = effective_rel;
     let current_log_level = g.log_level;
-    
+    let send_start = std::time::Instant::now();
+
     let res = rt.block_on(async {
         // Helper to perform one send attempt
         async fn send_once(
@@ -1292,7 +4419,7 @@ pub extern "C" fn lk_send_data_ex(
         }
 
         // First attempt
-        match send_once(room, &topic, &payload).await {
+        match send_once(&room, &topic, &payload).await {
             Ok(_) => Ok(()),
             Err(e1) => {
                 // Brief backoff then one retry; common when engine is still settling right after join
@@ -1300,7 +4427,7 @@ pub extern "C" fn lk_send_data_ex(
                     println!("[livekit_ffi] send_data first attempt failed, retrying: {}", e1);
                 }
                 tokio::time::sleep(Duration::from_millis(100)).await;
-                send_once(room, &topic, &payload).await
+                send_once(&room, &topic, &payload).await
             }
         }
     });
@@ -1311,12 +4438,19 @@ pub extern "C" fn lk_send_data_ex(
             match effective_rel_copy {
                 LkReliability::Reliable => {
                     stats.reliable_sent_bytes.fetch_add(len as i64, Ordering::Relaxed);
+                    stats.reliable_msgs_sent.fetch_add(1, Ordering::Relaxed);
+                    // `writer.close()` completing is the closest thing to an ack this
+                    // transport exposes for a reliable send; smooth with a fixed
+                    // per-sample alpha since this isn't tied to the tick-based accumulator.
+                    const ACK_EWMA_ALPHA: f64 = 0.2;
+                    let ack_ms = send_start.elapsed().as_secs_f64() * 1000.0;
+                    ewma_update(&stats.avg_reliable_ack_ms, ack_ms, ACK_EWMA_ALPHA);
                 }
                 LkReliability::Lossy => {
                     stats.lossy_sent_bytes.fetch_add(len as i64, Ordering::Relaxed);
                 }
             }
-            lk_log!(g, LkLogLevel::Debug, "Sent data: {} bytes, topic='{}'", len, topic);
+            lk_log!(g, LkLogLevel::Debug, LkLogCategory::Data, "Sent data: {} bytes, topic='{}'", len, topic);
             ok()
         },
         Err(e) => {
@@ -1330,12 +4464,370 @@ pub extern "C" fn lk_send_data_ex(
                 }
             }
             let msg = format!("byte_stream write failed: {}", e);
-            lk_log!(g, LkLogLevel::Error, "{}", msg);
+            lk_log!(g, LkLogLevel::Error, LkLogCategory::Data, "{}", msg);
             err(203, &msg)
         },
     }
 }
 
+/// Sends `payload` as an RPC request on the dedicated `lk-rpc` topic and
+/// registers it in `rpc_pending`; the timeout timer (see
+/// `spawn_rpc_timeout_task`) and `handle_rpc_frame` both resolve entries out
+/// of that map, so `reply_cb` fires exactly once either way.
+///
+/// # Safety
+/// `method` must be a valid NUL-terminated string and `payload` must point to
+/// at least `len` readable bytes (or be anything when `len` is 0).
+#[no_mangle]
+pub unsafe extern "C" fn lk_rpc_call(
+    client: *mut LkClientHandle,
+    method: *const c_char,
+    payload: *const u8,
+    len: usize,
+    timeout_ms: u32,
+    reply_cb: RpcReplyFn,
+    user: *mut c_void,
+) -> LkResult {
+    if client.is_null() {
+        return err(1, "client null");
+    }
+    let method = match cstr(method) {
+        Ok(s) => s,
+        Err(_) => return err(4, "method null or invalid"),
+    };
+    if len > 0 && payload.is_null() {
+        return err(4, "payload null");
+    }
+    if len > RPC_MAX_PAYLOAD {
+        return err(202, &format!("rpc payload size {} exceeds limit {}", len, RPC_MAX_PAYLOAD));
+    }
+
+    let c = &*(client as *const Client);
+    let mut g = c.0.lock().unwrap();
+    let room = match g.room.as_ref() {
+        Some(r) => r.clone(),
+        None => return err(6, "not connected"),
+    };
+
+    let payload_slice = if len == 0 { &[][..] } else { std::slice::from_raw_parts(payload, len) };
+    let request_id = g.next_rpc_id;
+    g.next_rpc_id += 1;
+    let frame = encode_rpc_request(request_id, method, payload_slice);
+    g.rpc_pending.insert(request_id, RpcPendingCall {
+        reply_cb,
+        user: UserPtr(user),
+        deadline: std::time::Instant::now() + Duration::from_millis(timeout_ms as u64),
+    });
+    let rt = g.rt.clone();
+    drop(g);
+
+    if let Err(e) = rt.block_on(send_rpc_frame(&room, &frame)) {
+        if let Ok(mut g) = c.0.lock() {
+            g.rpc_pending.remove(&request_id);
+        }
+        let msg = format!("rpc send failed: {}", e);
+        if let Ok(mut g) = c.0.lock() {
+            lk_log!(g, LkLogLevel::Error, LkLogCategory::Data, "{}", msg);
+        }
+        return err(203, &msg);
+    }
+
+    ok()
+}
+
+/// Registers (or replaces) the handler for inbound RPC requests on `method`.
+/// Calls with an unregistered method get back `RPC_STATUS_NO_HANDLER`.
+///
+/// # Safety
+/// `method` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn lk_rpc_register_handler(
+    client: *mut LkClientHandle,
+    method: *const c_char,
+    handler: RpcHandlerFn,
+    user: *mut c_void,
+) -> LkResult {
+    if client.is_null() {
+        return err(1, "client null");
+    }
+    let method = match cstr(method) {
+        Ok(s) => s,
+        Err(_) => return err(4, "method null or invalid"),
+    };
+
+    let c = &*(client as *const Client);
+    let mut g = c.0.lock().unwrap();
+    g.rpc_handlers.insert(method.to_string(), RpcHandlerEntry {
+        handler,
+        user: UserPtr(user),
+    });
+
+    ok()
+}
+
+/// Sends `payload` as a fire-and-forget RPC Event on the `lk-rpc` topic: no
+/// correlation id is registered and no Response frame is ever awaited, unlike
+/// `lk_rpc_call`. Useful for one-way notifications (agent state pushes) that
+/// don't need a reply.
+///
+/// This reuses the request/response RPC transport `lk_rpc_call`/
+/// `lk_rpc_register_handler` already built (the `u64 request_id | u8 kind | u8
+/// status | u32 method_len | method | payload` frame on the dedicated
+/// `lk-rpc` byte-stream topic) rather than introducing a second,
+/// differently-framed RPC stack. `request_id` is simply 0 on an Event frame
+/// since nothing correlates a reply to it.
+///
+/// # Safety
+/// `method` must be a valid NUL-terminated string and `payload` must point to
+/// at least `len` readable bytes (or be anything when `len` is 0).
+#[no_mangle]
+pub unsafe extern "C" fn lk_rpc_send_event(
+    client: *mut LkClientHandle,
+    method: *const c_char,
+    payload: *const u8,
+    len: usize,
+) -> LkResult {
+    if client.is_null() {
+        return err(1, "client null");
+    }
+    let method = match cstr(method) {
+        Ok(s) => s,
+        Err(_) => return err(4, "method null or invalid"),
+    };
+    if len > 0 && payload.is_null() {
+        return err(4, "payload null");
+    }
+    if len > RPC_MAX_PAYLOAD {
+        return err(202, &format!("rpc payload size {} exceeds limit {}", len, RPC_MAX_PAYLOAD));
+    }
+
+    let c = &*(client as *const Client);
+    let g = c.0.lock().unwrap();
+    let room = match g.room.as_ref() {
+        Some(r) => r.clone(),
+        None => return err(6, "not connected"),
+    };
+    let rt = g.rt.clone();
+    drop(g);
+
+    let payload_slice = if len == 0 { &[][..] } else { std::slice::from_raw_parts(payload, len) };
+    let frame = encode_rpc_event(method, payload_slice);
+    if let Err(e) = rt.block_on(send_rpc_frame(&room, &frame)) {
+        let msg = format!("rpc event send failed: {}", e);
+        if let Ok(mut g) = c.0.lock() {
+            lk_log!(g, LkLogLevel::Error, LkLogCategory::Data, "{}", msg);
+        }
+        return err(203, &msg);
+    }
+
+    ok()
+}
+
+/// Registers (or replaces) the sink for inbound RPC Event frames sent via the
+/// peer's `lk_rpc_send_event`.
+#[no_mangle]
+pub extern "C" fn lk_rpc_set_event_callback(
+    client: *mut LkClientHandle,
+    cb: Option<RpcEventFn>,
+    user: *mut c_void,
+) -> LkResult {
+    if client.is_null() {
+        return err(1, "client null");
+    }
+    let c = unsafe { &*(client as *const Client) };
+    let mut g = c.0.lock().unwrap();
+    g.rpc_event_cb = cb.map(|f| (f, UserPtr(user)));
+    ok()
+}
+
+/// Encodes `frame` as a Live Link Face packet and sends it on the dedicated
+/// `lk-face` byte-stream topic, so `LkDataStats.face_frames_*` can track it
+/// separately from the generic `lk_send_data_ex` counters even though it
+/// rides the same size-limit rules.
+///
+/// # Safety
+/// `frame` must point to a valid `LkFaceFrame` whose `device_id`/
+/// `subject_name` are valid NUL-terminated strings and whose `blendshapes`
+/// points to at least `blendshape_count` readable `f32`s.
+#[no_mangle]
+pub unsafe extern "C" fn lk_publish_face_frame(
+    client: *mut LkClientHandle,
+    frame: *const LkFaceFrame,
+    reliability: LkReliability,
+) -> LkResult {
+    if client.is_null() {
+        return err(1, "client null");
+    }
+    if frame.is_null() {
+        return err(4, "frame null");
+    }
+
+    let c = &*(client as *const Client);
+    let g = c.0.lock().unwrap();
+    let room = match g.room.as_ref() {
+        Some(r) => r.clone(),
+        None => return err(6, "not connected"),
+    };
+
+    let payload = match encode_face_frame(&*frame) {
+        Ok(p) => p,
+        Err(e) => return err(5, &format!("invalid face frame: {}", e)),
+    };
+
+    const LOSSY_MAX: usize = 1300;
+    const RELIABLE_MAX: usize = 15 * 1024;
+    let len = payload.len();
+    match reliability {
+        LkReliability::Lossy if len > LOSSY_MAX => {
+            return err(201, &format!("lossy face frame size {} exceeds limit {}", len, LOSSY_MAX));
+        }
+        LkReliability::Reliable if len > RELIABLE_MAX => {
+            return err(202, &format!("reliable face frame size {} exceeds limit {}", len, RELIABLE_MAX));
+        }
+        _ => {}
+    }
+
+    let stats = g.data_stats.clone();
+    let rt = g.rt.clone();
+    drop(g);
+
+    match rt.block_on(send_face_frame(&room, &payload)) {
+        Ok(_) => {
+            stats.face_frames_sent.fetch_add(1, Ordering::Relaxed);
+            ok()
+        }
+        Err(e) => {
+            stats.face_frames_dropped.fetch_add(1, Ordering::Relaxed);
+            err(203, &format!("face frame send failed: {}", e))
+        }
+    }
+}
+
+/// Starts buffering decoded frames of the subscribed video track `track_sid`
+/// for an animated GIF preview. Frames are captured opportunistically off
+/// whatever rate the track actually decodes at, throttled down to `fps` and
+/// downscaled to `max_dimension`; nothing is captured for a track that isn't
+/// currently subscribed (the handle just sits empty until one is).
+///
+/// # Safety
+/// `track_sid` must be a valid NUL-terminated string; `out_handle` must point
+/// to valid writable memory, and the returned handle must eventually be
+/// passed to `lk_finish_gif_capture`.
+#[no_mangle]
+pub unsafe extern "C" fn lk_start_gif_capture(
+    client: *mut LkClientHandle,
+    track_sid: *const c_char,
+    fps: c_int,
+    max_frames: c_int,
+    max_dimension: c_int,
+    out_handle: *mut *mut LkGifCaptureHandle,
+) -> LkResult {
+    if client.is_null() {
+        return err(1, "client null");
+    }
+    if out_handle.is_null() {
+        return err(4, "out_handle null");
+    }
+    let sid = match cstr(track_sid) {
+        Ok(s) if !s.is_empty() => s.to_string(),
+        _ => return err(5, "track_sid null or empty"),
+    };
+    if fps <= 0 || max_frames <= 0 || max_dimension <= 0 {
+        return err(5, "invalid gif capture parameters");
+    }
+
+    let c = &*(client as *const Client);
+    let mut g = c.0.lock().unwrap();
+    let capture_id = g.next_gif_capture_id;
+    g.next_gif_capture_id += 1;
+    g.gif_captures.insert(
+        capture_id,
+        Arc::new(Mutex::new(GifCapture {
+            track_sid: sid,
+            fps: fps as u32,
+            max_frames: max_frames as usize,
+            max_dimension: max_dimension as u32,
+            frame_interval: Duration::from_secs_f64(1.0 / fps as f64),
+            last_captured_at: None,
+            frames: VecDeque::new(),
+        })),
+    );
+
+    let handle = Box::new(LkGifCaptureHandle(GifCaptureHandleRef {
+        client: c.0.clone(),
+        capture_id,
+    }));
+    *out_handle = Box::into_raw(handle);
+    ok()
+}
+
+/// Stops buffering, quantizes and encodes whatever frames were captured into
+/// an animated GIF, and hands the bytes back via `out_buffer`. Consumes
+/// `handle`; encoding runs synchronously on the calling thread, so call this
+/// off the game thread for anything but a handful of small frames.
+///
+/// # Safety
+/// `handle` must be one previously returned by `lk_start_gif_capture` and not
+/// already finished. `out_buffer` must point to valid writable memory and its
+/// contents must be released with `lk_free_buffer`.
+#[no_mangle]
+pub unsafe extern "C" fn lk_finish_gif_capture(
+    handle: *mut LkGifCaptureHandle,
+    out_buffer: *mut LkBuffer,
+) -> LkResult {
+    if handle.is_null() {
+        return err(1, "handle null");
+    }
+    if out_buffer.is_null() {
+        return err(4, "out_buffer null");
+    }
+    let handle = Box::from_raw(handle);
+    let client = handle.0.client.clone();
+    let capture_id = handle.0.capture_id;
+    drop(handle);
+
+    let capture = {
+        let mut g = client.lock().unwrap();
+        match g.gif_captures.remove(&capture_id) {
+            Some(c) => c,
+            None => return err(6, "gif capture already finished"),
+        }
+    };
+    let (frames, fps) = {
+        let capture = capture.lock().unwrap();
+        (
+            capture
+                .frames
+                .iter()
+                .map(|f| GifFrame { rgba: f.rgba.clone(), width: f.width, height: f.height })
+                .collect::<Vec<_>>(),
+            capture.fps,
+        )
+    };
+
+    let mut bytes = match encode_gif(&frames, fps) {
+        Ok(b) => b,
+        Err(e) => return err(7, &format!("gif encode failed: {e}")),
+    };
+    bytes.shrink_to_fit();
+    let len = bytes.len();
+    let data = bytes.as_mut_ptr();
+    std::mem::forget(bytes);
+    *out_buffer = LkBuffer { data, len };
+    ok()
+}
+
+/// # Safety
+/// The caller must only pass an `LkBuffer` previously returned by this FFI
+/// layer (currently just `lk_finish_gif_capture`).
+#[no_mangle]
+pub unsafe extern "C" fn lk_free_buffer(buf: LkBuffer) {
+    if buf.data.is_null() {
+        return;
+    }
+    let _ = Vec::from_raw_parts(buf.data, buf.len, buf.len);
+}
+
 // --------- Statistics Functions ---------
 
 /// # Safety
@@ -1407,7 +4899,137 @@ pub unsafe extern "C" fn lk_get_data_stats(
         reliable_dropped: g.data_stats.reliable_dropped.load(Ordering::Relaxed),
         lossy_sent_bytes: g.data_stats.lossy_sent_bytes.load(Ordering::Relaxed),
         lossy_dropped: g.data_stats.lossy_dropped.load(Ordering::Relaxed),
+        face_frames_sent: g.data_stats.face_frames_sent.load(Ordering::Relaxed),
+        face_frames_dropped: g.data_stats.face_frames_dropped.load(Ordering::Relaxed),
+        reliable_bps: load_f64(&g.data_stats.reliable_bps),
+        lossy_bps: load_f64(&g.data_stats.lossy_bps),
+        reliable_msgs_per_sec: load_f64(&g.data_stats.reliable_msgs_per_sec),
+        avg_reliable_ack_ms: load_f64(&g.data_stats.avg_reliable_ack_ms),
     };
-    
+
+    ok()
+}
+
+/// # Safety
+/// The caller must ensure `out_stats` points to valid writable memory, and
+/// must release the returned report with `lk_free_connection_stats`.
+///
+/// The `livekit` crate doesn't surface the native `RTCPeerConnection`
+/// getStats report through its public `Room`/track API, so per-track
+/// outbound/inbound RTP counters and the ICE candidate-pair fields below are
+/// zeroed and their `stats_available` flag is left at `0` rather than
+/// guessed at; this call does establish the real set of published/subscribed
+/// track SIDs and directions now, so UE-side code can be written against the
+/// final ABI and start getting live numbers the moment a future SDK bump
+/// exposes the underlying report.
+#[no_mangle]
+pub unsafe extern "C" fn lk_get_connection_stats(
+    client: *mut LkClientHandle,
+    out_stats: *mut LkConnectionStats,
+) -> LkResult {
+    if client.is_null() {
+        return err(1, "client null");
+    }
+    if out_stats.is_null() {
+        return err(4, "out_stats null");
+    }
+
+    let c = &*(client as *const Client);
+    let g = c.0.lock().unwrap();
+    if g.room.is_none() {
+        return err(6, "not connected");
+    }
+
+    let mut entries: Vec<LkTrackStatsEntry> = Vec::new();
+    for pipeline in g.audio_tracks.values() {
+        entries.push(LkTrackStatsEntry {
+            track_sid: CString::new(pipeline.local_track.sid().to_string())
+                .unwrap_or_default()
+                .into_raw(),
+            direction: LkTrackDirection::Outbound,
+            stats_available: 0,
+            bytes: 0,
+            packets: 0,
+            packets_lost: 0,
+            retransmitted_packets: 0,
+            jitter_ms: 0.0,
+            target_bitrate_bps: 0,
+            actual_bitrate_bps: 0,
+            frames_encoded: 0,
+            frames_decoded: 0,
+            frames_dropped: 0,
+            qp: 0.0,
+        });
+    }
+    for pipeline in g.video_tracks.values() {
+        entries.push(LkTrackStatsEntry {
+            track_sid: CString::new(pipeline.local_track.sid().to_string())
+                .unwrap_or_default()
+                .into_raw(),
+            direction: LkTrackDirection::Outbound,
+            stats_available: 0,
+            bytes: 0,
+            packets: 0,
+            packets_lost: 0,
+            retransmitted_packets: 0,
+            jitter_ms: 0.0,
+            target_bitrate_bps: 0,
+            actual_bitrate_bps: 0,
+            frames_encoded: 0,
+            frames_decoded: 0,
+            frames_dropped: 0,
+            qp: 0.0,
+        });
+    }
+    for sid in g.remote_audio_streams.keys().chain(g.remote_video_streams.keys()) {
+        entries.push(LkTrackStatsEntry {
+            track_sid: CString::new(sid.as_str()).unwrap_or_default().into_raw(),
+            direction: LkTrackDirection::Inbound,
+            stats_available: 0,
+            bytes: 0,
+            packets: 0,
+            packets_lost: 0,
+            retransmitted_packets: 0,
+            jitter_ms: 0.0,
+            target_bitrate_bps: 0,
+            actual_bitrate_bps: 0,
+            frames_encoded: 0,
+            frames_decoded: 0,
+            frames_dropped: 0,
+            qp: 0.0,
+        });
+    }
+
+    entries.shrink_to_fit();
+    let track_count = entries.len();
+    let tracks_ptr = entries.as_mut_ptr();
+    std::mem::forget(entries);
+
+    *out_stats = LkConnectionStats {
+        tracks: tracks_ptr,
+        track_count,
+        ice: LkIceStats {
+            stats_available: 0,
+            current_rtt_ms: 0.0,
+            available_outgoing_bitrate_bps: 0,
+        },
+    };
+
     ok()
 }
+
+/// # Safety
+/// The caller must only pass a `LkConnectionStats` previously returned by
+/// `lk_get_connection_stats`.
+#[no_mangle]
+pub unsafe extern "C" fn lk_free_connection_stats(stats: LkConnectionStats) {
+    if stats.tracks.is_null() {
+        return;
+    }
+    let entries = Vec::from_raw_parts(stats.tracks, stats.track_count, stats.track_count);
+    for entry in entries {
+        if !entry.track_sid.is_null() {
+            let _ = CString::from_raw(entry.track_sid);
+        }
+    }
+}
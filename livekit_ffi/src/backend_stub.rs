@@ -22,11 +22,44 @@ pub unsafe extern "C" fn lk_free_str(p: *mut c_char) {
     }
 }
 
+/// Mirrors the cubeb-style model in backend_livekit.rs: only the `*LE`
+/// variants are actually supported (this crate never byte-swaps PCM).
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LkSampleFormat { S16LE = 0, S16BE = 1, Float32LE = 2, Float32BE = 3 }
+
+const SUPPORTED_SAMPLE_RATES: [c_int; 6] = [8000, 16000, 24000, 32000, 44100, 48000];
+
+fn is_supported_audio_format(format: LkSampleFormat, sample_rate: c_int, channels: c_int) -> bool {
+    (format == LkSampleFormat::S16LE || format == LkSampleFormat::Float32LE)
+        && (channels == 1 || channels == 2)
+        && SUPPORTED_SAMPLE_RATES.contains(&sample_rate)
+}
+
 #[repr(C)] pub enum LkReliability { Reliable = 0, Lossy = 1 }
 #[repr(C)] pub enum LkRole { Auto = 0, Publisher = 1, Subscriber = 2, Both = 3 }
 #[repr(C)] pub enum LkConnectionState { Connecting = 0, Connected = 1, Reconnecting = 2, Disconnected = 3, Failed = 4 }
 #[repr(C)] pub enum LkLogLevel { Error = 0, Warn = 1, Info = 2, Debug = 3, Trace = 4 }
+#[repr(C)] pub enum LkLogCategory { Connection = 0, Audio = 1, Data = 2, Rtc = 3 }
+
+#[repr(C)]
+pub struct LkLogRecord {
+    pub timestamp_ns: i64,
+    pub level: LkLogLevel,
+    pub category: LkLogCategory,
+    pub message: *const c_char,
+}
+#[repr(C)] pub enum LkVideoFormat { I420 = 0, Nv12 = 1, Rgba = 2, Bgra = 3 }
 #[repr(C)] pub struct LkClientHandle { _private: [u8;0] }
+#[repr(C)] pub struct LkVideoTrackHandle { _private: [u8;0] }
+
+#[repr(C)]
+pub struct LkVideoTrackConfig {
+    pub track_name: *const c_char,
+    pub width: c_int,
+    pub height: c_int,
+    pub max_queued_frames: c_int,
+}
 
 #[repr(C)]
 pub struct LkAudioStats {
@@ -44,6 +77,58 @@ pub struct LkDataStats {
     pub reliable_dropped: i64,
     pub lossy_sent_bytes: i64,
     pub lossy_dropped: i64,
+    pub face_frames_sent: i64,
+    pub face_frames_dropped: i64,
+    pub reliable_bps: f64,
+    pub lossy_bps: f64,
+    pub reliable_msgs_per_sec: f64,
+    pub avg_reliable_ack_ms: f64,
+}
+
+#[repr(C)] pub enum LkTrackDirection { Outbound = 0, Inbound = 1 }
+
+#[repr(C)]
+pub struct LkTrackStatsEntry {
+    pub track_sid: *mut c_char,
+    pub direction: LkTrackDirection,
+    pub stats_available: c_int,
+    pub bytes: i64,
+    pub packets: i64,
+    pub packets_lost: i64,
+    pub retransmitted_packets: i64,
+    pub jitter_ms: f64,
+    pub target_bitrate_bps: i64,
+    pub actual_bitrate_bps: i64,
+    pub frames_encoded: i64,
+    pub frames_decoded: i64,
+    pub frames_dropped: i64,
+    pub qp: f64,
+}
+
+#[repr(C)]
+pub struct LkIceStats {
+    pub stats_available: c_int,
+    pub current_rtt_ms: f64,
+    pub available_outgoing_bitrate_bps: i64,
+}
+
+#[repr(C)]
+pub struct LkConnectionStats {
+    pub tracks: *mut LkTrackStatsEntry,
+    pub track_count: usize,
+    pub ice: LkIceStats,
+}
+
+#[repr(C)]
+pub struct LkFaceFrame {
+    pub device_id: *const c_char,
+    pub subject_name: *const c_char,
+    pub frame_number: c_int,
+    pub subframe: c_int,
+    pub frame_rate_num: c_int,
+    pub frame_rate_den: c_int,
+    pub blendshapes: *const c_float,
+    pub blendshape_count: usize,
 }
 
 struct ClientState { connected: bool }
@@ -78,6 +163,129 @@ struct Client(std::sync::Arc<std::sync::Mutex<ClientState>>);
     _user: *mut c_void
 ) -> LkResult { ok() }
 
+#[no_mangle] pub extern "C" fn lk_client_set_audio_callback_ex(
+    _client: *mut LkClientHandle,
+    _cb: Option<extern "C" fn(user:*mut c_void, identity:*const c_char, track_sid:*const c_char, pcm:*const i16, frames_per_channel:usize, channels:c_int, sample_rate:c_int)>,
+    _user: *mut c_void
+) -> LkResult { ok() }
+
+#[no_mangle] pub extern "C" fn lk_client_set_video_callback(
+    _client: *mut LkClientHandle,
+    _cb: Option<extern "C" fn(user:*mut c_void, buf:*const u8, width:c_int, height:c_int, stride:c_int, format:LkVideoFormat, timestamp_us:i64)>,
+    _user: *mut c_void
+) -> LkResult { ok() }
+
+#[no_mangle] pub extern "C" fn lk_publish_video_track(
+    client: *mut LkClientHandle,
+    config: *const LkVideoTrackConfig,
+    out_track: *mut *mut LkVideoTrackHandle,
+) -> LkResult {
+    if client.is_null() { return err("client null", 1); }
+    if config.is_null() || out_track.is_null() { return err("bad params", 3); }
+    let cfg = unsafe { &*config };
+    if cfg.width <= 0 || cfg.height <= 0 { return err("bad params", 3); }
+    unsafe { *out_track = Box::into_raw(Box::new(LkVideoTrackHandle { _private: [] })); }
+    ok()
+}
+
+#[no_mangle] pub extern "C" fn lk_video_track_destroy(track: *mut LkVideoTrackHandle) -> LkResult {
+    if track.is_null() { return err("track null", 1); }
+    unsafe { drop(Box::from_raw(track)); }
+    ok()
+}
+
+#[no_mangle] pub extern "C" fn lk_push_video_frame(
+    track: *mut LkVideoTrackHandle,
+    buf: *const u8,
+    _len: usize,
+    _format: LkVideoFormat,
+    _timestamp_us: i64,
+) -> LkResult {
+    if track.is_null() { return err("track null", 1); }
+    if buf.is_null() { return err("buf null", 4); }
+    ok()
+}
+
+#[no_mangle] pub extern "C" fn lk_set_active_speakers_callback(
+    _client: *mut LkClientHandle,
+    _cb: Option<extern "C" fn(user:*mut c_void, identities:*const *const c_char, levels:*const c_float, count:usize)>,
+    _user: *mut c_void
+) -> LkResult { ok() }
+
+#[no_mangle] pub extern "C" fn lk_set_track_muted_callback(
+    _client: *mut LkClientHandle,
+    _cb: Option<extern "C" fn(user:*mut c_void, identity:*const c_char, track_sid:*const c_char, muted:c_int)>,
+    _user: *mut c_void
+) -> LkResult { ok() }
+
+#[no_mangle] pub extern "C" fn lk_set_video_track_subscribed_callback(
+    _client: *mut LkClientHandle,
+    _cb: Option<extern "C" fn(user:*mut c_void, identity:*const c_char, track_sid:*const c_char)>,
+    _user: *mut c_void
+) -> LkResult { ok() }
+
+#[no_mangle] pub extern "C" fn lk_set_video_track_unsubscribed_callback(
+    _client: *mut LkClientHandle,
+    _cb: Option<extern "C" fn(user:*mut c_void, identity:*const c_char, track_sid:*const c_char)>,
+    _user: *mut c_void
+) -> LkResult { ok() }
+
+#[no_mangle] pub extern "C" fn lk_set_face_frame_callback(
+    _client: *mut LkClientHandle,
+    _cb: Option<extern "C" fn(user:*mut c_void, frame:*const LkFaceFrame)>,
+    _user: *mut c_void
+) -> LkResult { ok() }
+
+#[no_mangle] pub extern "C" fn lk_publish_face_frame(
+    client: *mut LkClientHandle,
+    _frame: *const LkFaceFrame,
+    _reliability: LkReliability,
+) -> LkResult {
+    if client.is_null() { return err("client null", 1); }
+    err("Live Link Face transport not supported in stub backend", 501)
+}
+
+#[repr(C)] pub struct LkBuffer { pub data: *mut u8, pub len: usize }
+#[repr(C)] pub struct LkGifCaptureHandle { _private: [u8; 0] }
+
+#[no_mangle] pub extern "C" fn lk_start_gif_capture(
+    client: *mut LkClientHandle,
+    _track_sid: *const c_char,
+    _fps: c_int,
+    _max_frames: c_int,
+    _max_dimension: c_int,
+    _out_handle: *mut *mut LkGifCaptureHandle,
+) -> LkResult {
+    if client.is_null() { return err("client null", 1); }
+    err("GIF capture not supported in stub backend", 501)
+}
+
+/// # Safety
+/// The caller must ensure `out_buffer` points to valid writable memory.
+#[no_mangle] pub unsafe extern "C" fn lk_finish_gif_capture(
+    handle: *mut LkGifCaptureHandle,
+    out_buffer: *mut LkBuffer,
+) -> LkResult {
+    if handle.is_null() { return err("handle null", 1); }
+    if out_buffer.is_null() { return err("out_buffer null", 1); }
+    *out_buffer = LkBuffer { data: std::ptr::null_mut(), len: 0 };
+    err("GIF capture not supported in stub backend", 501)
+}
+
+#[no_mangle] pub extern "C" fn lk_free_buffer(_buf: LkBuffer) {}
+
+#[no_mangle] pub extern "C" fn lk_set_stream_cb(
+    _client: *mut LkClientHandle,
+    _open_cb: Option<extern "C" fn(user:*mut c_void, stream_id:u64, topic:*const c_char, identity:*const c_char, total_length:i64)>,
+    _chunk_cb: Option<extern "C" fn(user:*mut c_void, stream_id:u64, offset:u64, ptr:*const u8, len:usize)>,
+    _close_cb: Option<extern "C" fn(user:*mut c_void, stream_id:u64, error_code:c_int, message:*const c_char)>,
+    _user: *mut c_void
+) -> LkResult { ok() }
+
+#[no_mangle] pub extern "C" fn lk_stream_abort(_client: *mut LkClientHandle, _stream_id: u64) -> LkResult {
+    err("no inbound streams in stub backend", 501)
+}
+
 #[no_mangle] pub extern "C" fn lk_set_audio_format_change_callback(
     _client: *mut LkClientHandle,
     _cb: Option<extern "C" fn(user:*mut c_void, sample_rate:c_int, channels:c_int)>,
@@ -134,10 +342,130 @@ struct Client(std::sync::Arc<std::sync::Mutex<ClientState>>);
 ) -> LkResult { ok() }
 
 #[no_mangle] pub extern "C" fn lk_set_audio_output_format(
-    _client:*mut LkClientHandle,
-    _sample_rate:c_int,
-    _channels:c_int
-) -> LkResult { ok() }
+    client:*mut LkClientHandle,
+    sample_rate:c_int,
+    channels:c_int,
+) -> LkResult {
+    // Delegate to lk_set_audio_output_format_ex with the format this
+    // function always assumed before _ex existed, so already-compiled
+    // callers of this signature keep working unchanged.
+    lk_set_audio_output_format_ex(client, sample_rate, channels, LkSampleFormat::S16LE)
+}
+
+#[no_mangle] pub extern "C" fn lk_set_audio_output_format_ex(
+    client:*mut LkClientHandle,
+    sample_rate:c_int,
+    channels:c_int,
+    format: LkSampleFormat,
+) -> LkResult {
+    if client.is_null() { return err("client null", 1); }
+    if !is_supported_audio_format(format, sample_rate, channels) {
+        return err("unsupported sample format/channel/rate combination", 8);
+    }
+    ok()
+}
+
+#[no_mangle] pub extern "C" fn lk_set_audio_format_changed_callback(
+    client: *mut LkClientHandle,
+    _cb: Option<extern "C" fn(user: *mut c_void, format: LkSampleFormat, sample_rate: c_int, channels: c_int)>,
+    _user: *mut c_void,
+) -> LkResult {
+    if client.is_null() { return err("client null", 1); }
+    ok()
+}
+
+#[repr(C)]
+pub struct LkDeviceList {
+    pub names: *mut *mut c_char,
+    pub count: usize,
+}
+
+/// # Safety
+/// The caller must ensure `out_list` points to valid writable memory.
+#[no_mangle] pub unsafe extern "C" fn lk_enumerate_input_devices(out_list: *mut LkDeviceList) -> LkResult {
+    if out_list.is_null() { return err("out_list null", 1); }
+    *out_list = LkDeviceList { names: std::ptr::null_mut(), count: 0 };
+    ok()
+}
+
+#[no_mangle] pub unsafe extern "C" fn lk_free_device_list(_list: LkDeviceList) {}
+
+/// # Safety
+/// The caller must ensure `out_sample_rate`/`out_channels` point to valid writable memory.
+#[no_mangle] pub unsafe extern "C" fn lk_query_default_input_format(
+    out_sample_rate: *mut c_int,
+    out_channels: *mut c_int,
+) -> LkResult {
+    if out_sample_rate.is_null() || out_channels.is_null() { return err("out params null", 1); }
+    *out_sample_rate = 0;
+    *out_channels = 0;
+    err("No input devices available in stub backend", 501)
+}
+
+#[no_mangle] pub extern "C" fn lk_start_capture_from_device(
+    client: *mut LkClientHandle,
+    _device_name: *const c_char,
+) -> LkResult {
+    if client.is_null() { return err("client null", 1); }
+    err("Device capture not supported in stub backend", 501)
+}
+
+#[no_mangle] pub extern "C" fn lk_stop_capture(client: *mut LkClientHandle) -> LkResult {
+    if client.is_null() { return err("client null", 1); }
+    err("Device capture not supported in stub backend", 501)
+}
+
+#[repr(C)] pub struct LkAudioRingHandle { _private: [u8; 0] }
+
+/// # Safety
+/// The caller must ensure `out_handle` points to valid writable memory.
+#[no_mangle] pub unsafe extern "C" fn lk_audio_ring_create(
+    client: *mut LkClientHandle,
+    _capacity_frames: c_int,
+    _sample_rate: c_int,
+    _channels: c_int,
+    _out_handle: *mut *mut LkAudioRingHandle,
+) -> LkResult {
+    if client.is_null() { return err("client null", 1); }
+    err("Audio ring not supported in stub backend", 501)
+}
+
+#[no_mangle] pub extern "C" fn lk_audio_ring_destroy(_handle: *mut LkAudioRingHandle) -> LkResult { ok() }
+
+/// # Safety
+/// The caller must ensure `data` points to at least `frames * channels` readable `i16`s.
+#[no_mangle] pub unsafe extern "C" fn lk_audio_ring_write(
+    _handle: *mut LkAudioRingHandle,
+    _data: *const i16,
+    _frames: usize,
+) -> usize { 0 }
+
+/// # Safety
+/// The caller must ensure `out` points to at least `frames * channels` writable `i16`s.
+#[no_mangle] pub unsafe extern "C" fn lk_audio_ring_read(
+    _handle: *mut LkAudioRingHandle,
+    _out: *mut i16,
+    _frames: usize,
+) -> usize { 0 }
+
+/// # Safety
+/// The caller must ensure `out_stats` points to valid writable memory.
+#[no_mangle] pub unsafe extern "C" fn lk_audio_ring_get_stats(
+    handle: *mut LkAudioRingHandle,
+    out_stats: *mut LkAudioStats,
+) -> LkResult {
+    if handle.is_null() { return err("handle null", 1); }
+    if out_stats.is_null() { return err("out_stats null", 1); }
+    *out_stats = LkAudioStats {
+        sample_rate: 0,
+        channels: 0,
+        ring_capacity_frames: 0,
+        ring_queued_frames: 0,
+        underruns: 0,
+        overruns: 0,
+    };
+    ok()
+}
 
 #[no_mangle] pub extern "C" fn lk_publish_audio_pcm_i16(
     client:*mut LkClientHandle,
@@ -148,6 +476,24 @@ struct Client(std::sync::Arc<std::sync::Mutex<ClientState>>);
 ) -> LkResult {
     if client.is_null() { return err("client null", 1); }
     if channels <= 0 || sample_rate <= 0 { return err("bad params", 3); }
+    if !is_supported_audio_format(LkSampleFormat::S16LE, sample_rate, channels) {
+        return err("unsupported sample format/channel/rate combination", 8);
+    }
+    ok()
+}
+
+#[no_mangle] pub extern "C" fn lk_publish_audio_pcm_f32(
+    client:*mut LkClientHandle,
+    _pcm:*const c_float,
+    _frames_per_ch: usize,
+    channels:c_int,
+    sample_rate:c_int
+) -> LkResult {
+    if client.is_null() { return err("client null", 1); }
+    if channels <= 0 || sample_rate <= 0 { return err("bad params", 3); }
+    if !is_supported_audio_format(LkSampleFormat::Float32LE, sample_rate, channels) {
+        return err("unsupported sample format/channel/rate combination", 8);
+    }
     ok()
 }
 
@@ -173,6 +519,74 @@ struct Client(std::sync::Arc<std::sync::Mutex<ClientState>>);
     ok()
 }
 
+type RpcReplyFn = extern "C" fn(user:*mut c_void, request_id:u64, status:c_int, payload:*const u8, payload_len:usize);
+type RpcHandlerFn = extern "C" fn(
+    user:*mut c_void,
+    method:*const c_char,
+    payload:*const u8,
+    payload_len:usize,
+    out_buf:*mut u8,
+    out_buf_cap:usize,
+    out_len:*mut usize,
+) -> c_int;
+
+#[no_mangle] pub extern "C" fn lk_rpc_call(
+    client:*mut LkClientHandle,
+    _method: *const c_char,
+    _payload: *const u8,
+    _len: usize,
+    _timeout_ms: u32,
+    _reply_cb: RpcReplyFn,
+    _user: *mut c_void,
+) -> LkResult {
+    if client.is_null() { return err("client null", 1); }
+    err("RPC not supported in stub backend", 501)
+}
+
+#[no_mangle] pub extern "C" fn lk_rpc_register_handler(
+    client:*mut LkClientHandle,
+    _method: *const c_char,
+    _handler: RpcHandlerFn,
+    _user: *mut c_void,
+) -> LkResult {
+    if client.is_null() { return err("client null", 1); }
+    err("RPC not supported in stub backend", 501)
+}
+
+type RpcEventFn = extern "C" fn(
+    user: *mut c_void,
+    method: *const c_char,
+    payload: *const u8,
+    payload_len: usize,
+);
+
+#[no_mangle] pub extern "C" fn lk_rpc_send_event(
+    client:*mut LkClientHandle,
+    _method: *const c_char,
+    _payload: *const u8,
+    _len: usize,
+) -> LkResult {
+    if client.is_null() { return err("client null", 1); }
+    err("RPC not supported in stub backend", 501)
+}
+
+#[no_mangle] pub extern "C" fn lk_rpc_set_event_callback(
+    client:*mut LkClientHandle,
+    _cb: Option<RpcEventFn>,
+    _user: *mut c_void,
+) -> LkResult {
+    if client.is_null() { return err("client null", 1); }
+    err("RPC not supported in stub backend", 501)
+}
+
+#[no_mangle] pub extern "C" fn lk_set_stats_window_ms(
+    client: *mut LkClientHandle,
+    _window_ms: c_int,
+) -> LkResult {
+    if client.is_null() { return err("client null", 1); }
+    ok()
+}
+
 #[no_mangle] pub extern "C" fn lk_set_default_data_labels(
     _client:*mut LkClientHandle,
     _reliable_label: *const c_char,
@@ -197,11 +611,43 @@ struct Client(std::sync::Arc<std::sync::Mutex<ClientState>>);
     _auto_subscribe: c_int
 ) -> LkResult { err("Dynamic role switching not supported in stub backend", 501) }
 
+#[no_mangle] pub extern "C" fn lk_set_deafened(client:*mut LkClientHandle, _deafened: c_int) -> LkResult {
+    if client.is_null() { return err("client null", 1); }
+    ok()
+}
+
+#[no_mangle] pub extern "C" fn lk_set_microphone_muted(client:*mut LkClientHandle, _muted: c_int) -> LkResult {
+    if client.is_null() { return err("client null", 1); }
+    ok()
+}
+
 #[no_mangle] pub extern "C" fn lk_set_log_level(
     _client:*mut LkClientHandle,
     _level: LkLogLevel
 ) -> LkResult { ok() }
 
+#[no_mangle] pub extern "C" fn lk_log_set_filter(
+    _client: *mut LkClientHandle,
+    _min_level: LkLogLevel,
+    _category_mask: c_int,
+) -> LkResult { ok() }
+
+/// # Safety
+/// `out_count` must point to a valid `usize`; the stub backend never records
+/// log lines, so it always writes 0.
+#[no_mangle] pub unsafe extern "C" fn lk_log_drain(
+    _client: *mut LkClientHandle,
+    _out_records: *mut LkLogRecord,
+    _max: usize,
+    out_count: *mut usize,
+) -> LkResult {
+    if out_count.is_null() { return err("null output pointer", 2); }
+    unsafe { *out_count = 0; }
+    ok()
+}
+
+#[no_mangle] pub extern "C" fn lk_log_clear(_client: *mut LkClientHandle) -> LkResult { ok() }
+
 /// # Safety
 /// The caller must ensure `out_stats` points to valid writable memory.
 #[no_mangle] pub unsafe extern "C" fn lk_get_audio_stats(
@@ -234,6 +680,33 @@ struct Client(std::sync::Arc<std::sync::Mutex<ClientState>>);
         reliable_dropped: 0,
         lossy_sent_bytes: 0,
         lossy_dropped: 0,
+        face_frames_sent: 0,
+        face_frames_dropped: 0,
+        reliable_bps: 0.0,
+        lossy_bps: 0.0,
+        reliable_msgs_per_sec: 0.0,
+        avg_reliable_ack_ms: 0.0,
     };
     ok()
 }
+
+/// # Safety
+/// The caller must ensure `out_stats` points to valid writable memory.
+#[no_mangle] pub unsafe extern "C" fn lk_get_connection_stats(
+    client: *mut LkClientHandle,
+    out_stats: *mut LkConnectionStats,
+) -> LkResult {
+    if client.is_null() { return err("client null", 1); }
+    if out_stats.is_null() { return err("out_stats null", 1); }
+    *out_stats = LkConnectionStats {
+        tracks: std::ptr::null_mut(),
+        track_count: 0,
+        ice: LkIceStats { stats_available: 0, current_rtt_ms: 0.0, available_outgoing_bitrate_bps: 0 },
+    };
+    err("Connection stats not supported in stub backend", 501)
+}
+
+/// # Safety
+/// The caller must only pass a `LkConnectionStats` previously returned by
+/// `lk_get_connection_stats`.
+#[no_mangle] pub unsafe extern "C" fn lk_free_connection_stats(_stats: LkConnectionStats) {}